@@ -0,0 +1,170 @@
+// src/common/src/condensed.rs
+//! CondensedMatrix utilities.
+use crate::Matrix;
+
+/// A symmetric matrix with a constant diagonal, storing only the upper
+/// triangle; defined as its size, the backing vector and the diagonal value.
+/// Entries are laid out row by row as in SciPy's `pdist`, i.e. (0, 1),
+/// (0, 2), ..., (0, n-1), (1, 2), ...
+#[derive(Debug, PartialEq)]
+pub struct CondensedMatrix<T>(usize, Vec<T>, T);
+
+/// Return the position of (i, j), i < j, within the condensed vector of an
+/// 'n' x 'n' matrix.
+pub fn condensed_index(n: usize, i: usize, j: usize) -> usize {
+    n * i - i * (i + 1) / 2 + (j - i - 1)
+}
+
+impl<T> CondensedMatrix<T>
+where
+    T: Default,
+{
+    /// Build a matrix from a condensed vector, such as the output of
+    /// SciPy's `pdist`. Panics if the length isn't n * (n - 1) / 2.
+    pub fn from_condensed(buf: Vec<T>) -> Self {
+        // Solve n * (n - 1) / 2 = len for n.
+        let n = (1.0 + (1.0 + 8.0 * buf.len() as f64).sqrt()) as usize / 2;
+        if n * n.saturating_sub(1) / 2 != buf.len() {
+            panic!("condensed vector has an invalid length!");
+        }
+
+        CondensedMatrix(n.max(1), buf, T::default())
+    }
+}
+
+impl<T> CondensedMatrix<T> {
+    /// The condensed vector; compatible with SciPy's `squareform`.
+    pub fn as_slice(&self) -> &[T] {
+        &self.1
+    }
+
+    /// Split the condensed vector into its rows; row i holds (i, i + 1..n).
+    pub fn rows_mut(&mut self) -> Vec<&mut [T]> {
+        let mut rows = Vec::with_capacity(self.0);
+        let mut rest = self.1.as_mut_slice();
+        for i in 0..self.0 {
+            let (row, tail) = rest.split_at_mut(self.0 - i - 1);
+            rows.push(row);
+            rest = tail;
+        }
+
+        rows
+    }
+
+    /// Return the position of (r, c) within the condensed vector, or None
+    /// on the diagonal.
+    fn index(&self, r: usize, c: usize) -> Option<usize> {
+        let (i, j) = match r < c {
+            true => (r, c),
+            false => (c, r),
+        };
+
+        match i == j {
+            true => None,
+            false => Some(condensed_index(self.0, i, j)),
+        }
+    }
+}
+
+impl<T> Matrix<T> for CondensedMatrix<T>
+where
+    T: Default + Clone,
+{
+    /// Panics if the matrix isn't square.
+    fn new(r: usize, c: usize) -> Self {
+        if r != c {
+            panic!("a condensed matrix must be square!");
+        }
+
+        CondensedMatrix(
+            r,
+            vec![T::default(); r * r.saturating_sub(1) / 2],
+            T::default(),
+        )
+    }
+
+    /// (rows, cols).
+    fn dim(&self) -> (usize, usize) {
+        (self.0, self.0)
+    }
+
+    /// Set both (r, c) and (c, r); the diagonal can't be set.
+    fn set(&mut self, r: usize, c: usize, val: T) {
+        if r >= self.0 || c >= self.0 {
+            panic!("call to set out of bounds!");
+        }
+
+        match self.index(r, c) {
+            Some(i) => self.1[i] = val,
+            None => panic!("call to set on the diagonal!"),
+        }
+    }
+
+    fn get(&self, r: usize, c: usize) -> Option<&T> {
+        if r >= self.0 || c >= self.0 {
+            return None;
+        }
+
+        match self.index(r, c) {
+            Some(i) => self.1.get(i),
+            None => Some(&self.2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condensed_matrix() {
+        let mut cm = CondensedMatrix::<f64>::new(4, 4);
+        assert_eq!(cm.dim(), (4, 4));
+        assert_eq!(cm.as_slice().len(), 6);
+
+        cm.set(1, 3, 2.0);
+        assert_eq!(cm.get(1, 3), Some(&2.0));
+        assert_eq!(cm.get(3, 1), Some(&2.0));
+        assert_eq!(cm.get(2, 2), Some(&0.0));
+        assert_eq!(cm.get(4, 0), None);
+    }
+
+    #[test]
+    fn test_condensed_matrix_pdist_order() {
+        let mut cm = CondensedMatrix::<usize>::new(4, 4);
+        let mut k = 0;
+        for i in 0..4 {
+            for j in i + 1..4 {
+                cm.set(j, i, k);
+                k += 1;
+            }
+        }
+
+        // (0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3).
+        assert_eq!(cm.as_slice(), &[0, 1, 2, 3, 4, 5]);
+
+        let rows = cm.rows_mut();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], &[3, 4]);
+        assert!(rows[3].is_empty());
+        assert_eq!(CondensedMatrix::from_condensed(vec![0, 1, 2, 3, 4, 5]), cm);
+    }
+
+    #[test]
+    fn test_condensed_matrix_from_condensed() {
+        let cm = CondensedMatrix::from_condensed(vec![1.0, 2.0, 3.0]);
+        assert_eq!(cm.dim(), (3, 3));
+        assert_eq!(cm.get(0, 2), Some(&2.0));
+        assert_eq!(cm.get(2, 1), Some(&3.0));
+
+        // A single point has no pairwise distances.
+        let cm = CondensedMatrix::<f64>::from_condensed(vec![]);
+        assert_eq!(cm.dim(), (1, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_condensed_matrix_invalid_length() {
+        CondensedMatrix::from_condensed(vec![1.0, 2.0]);
+    }
+}
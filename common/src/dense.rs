@@ -8,13 +8,14 @@ use crate::{Matrix, MatrixOps};
 #[derive(Debug)]
 pub struct DenseMatrix<T>(usize, usize, Vec<T>);
 
-impl<T> Matrix<T> for DenseMatrix<T> {
+impl<T> Matrix<T> for DenseMatrix<T>
+where
+    T: Default,
+{
+    /// Each entry starts as the default of 'T'; handing out the backing
+    /// vector uninitialized leaves every entry not yet set undefined.
     fn new(r: usize, c: usize) -> Self {
-        let mut buf = Vec::with_capacity(r * c);
-        unsafe {
-            buf.set_len(r * c);
-        }
-
+        let buf = std::iter::repeat_with(T::default).take(r * c).collect();
         DenseMatrix(r, c, buf)
     }
 
@@ -115,6 +116,19 @@ mod tests {
         assert_eq!(dm.get(1, 1), Some(&true));
     }
 
+    #[test]
+    fn test_dense_matrix_default() {
+        // Entries not yet set hold the default.
+        let dm = DenseMatrix::<usize>::new(3, 5);
+        assert!((0..3).all(|r| (0..5).all(|c| dm.get(r, c) == Some(&0))));
+
+        // Even for a type that isn't Clone.
+        #[derive(Debug, Default, PartialEq)]
+        struct Cell(usize);
+        let dm = DenseMatrix::<Cell>::new(2, 2);
+        assert_eq!(dm.get(1, 1), Some(&Cell(0)));
+    }
+
     #[test]
     fn test_col_swap() {
         let mut dm = DenseMatrix::<usize>::new(4, 4);
@@ -0,0 +1,107 @@
+// src/common/src/edge_list.rs
+//! EdgeList utilities.
+use crate::Matrix;
+
+/// An undirected graph on 'n' vertices, holding only its edges; each vertex
+/// keeps a list of (neighbor, value) sorted by neighbor. Viewed as a Matrix
+/// it is symmetric, so setting (r, c) also sets (c, r).
+#[derive(Debug, PartialEq)]
+pub struct EdgeList<T>(Vec<Vec<(usize, T)>>);
+
+impl<T> EdgeList<T> {
+    /// Return the number of (undirected) edges.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|nbrs| nbrs.len()).sum::<usize>() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|nbrs| nbrs.is_empty())
+    }
+
+    /// Return the neighbors of a vertex as (neighbor, value), ordered by
+    /// neighbor.
+    pub fn neighbors(&self, v: usize) -> &[(usize, T)] {
+        &self.0[v]
+    }
+
+    /// Iterate over each edge once as (a, b, value), with a < b.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.0.iter().enumerate().flat_map(|(a, nbrs)| {
+            nbrs.iter()
+                .filter(move |(b, _)| a < *b)
+                .map(move |(b, val)| (a, *b, val))
+        })
+    }
+
+    fn insert(&mut self, r: usize, c: usize, val: T) {
+        let nbrs = &mut self.0[r];
+        match nbrs.binary_search_by_key(&c, |(x, _)| *x) {
+            Ok(i) => nbrs[i].1 = val,
+            Err(i) => nbrs.insert(i, (c, val)),
+        }
+    }
+}
+
+impl<T> Matrix<T> for EdgeList<T>
+where
+    T: Clone,
+{
+    /// Panics if the matrix isn't square.
+    fn new(r: usize, c: usize) -> Self {
+        if r != c {
+            panic!("an edge list must be square!");
+        }
+
+        EdgeList((0..r).map(|_| Vec::new()).collect())
+    }
+
+    /// (rows, cols).
+    fn dim(&self) -> (usize, usize) {
+        (self.0.len(), self.0.len())
+    }
+
+    /// Add the edge (r, c); self loops are ignored.
+    fn set(&mut self, r: usize, c: usize, val: T) {
+        if r >= self.0.len() || c >= self.0.len() {
+            panic!("call to set out of bounds!");
+        }
+
+        if r != c {
+            self.insert(r, c, val.clone());
+            self.insert(c, r, val);
+        }
+    }
+
+    fn get(&self, r: usize, c: usize) -> Option<&T> {
+        let nbrs = self.0.get(r)?;
+        nbrs.binary_search_by_key(&c, |(x, _)| *x)
+            .ok()
+            .map(|i| &nbrs[i].1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_list() {
+        let mut el = EdgeList::<f64>::new(4, 4);
+        assert_eq!(el.dim(), (4, 4));
+        assert!(el.is_empty());
+
+        el.set(2, 0, 1.5);
+        el.set(0, 3, 2.5);
+        el.set(1, 1, 9.0);
+        assert_eq!(el.len(), 2);
+
+        assert_eq!(el.get(0, 2), Some(&1.5));
+        assert_eq!(el.get(2, 0), Some(&1.5));
+        assert_eq!(el.get(1, 1), None);
+        assert_eq!(el.get(5, 1), None);
+        assert_eq!(el.neighbors(0), &[(2, 1.5), (3, 2.5)]);
+
+        let edges: Vec<(usize, usize, &f64)> = el.iter().collect();
+        assert_eq!(edges, vec![(0, 2, &1.5), (0, 3, &2.5)]);
+    }
+}
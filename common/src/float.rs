@@ -0,0 +1,111 @@
+// src/common/src/float.rs
+//! A floating point abstraction, allowing f32 data to remain f32.
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Div, Mul, Sub};
+
+/// The operations required of a floating point type by distance, neighborhood
+/// and filtration computations. Implemented for f32 and f64.
+pub trait Float:
+    Copy
+    + Default
+    + PartialOrd
+    + Debug
+    + Display
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Into<f64>
+    + std::iter::Sum
+{
+    const ZERO: Self;
+    const INFINITY: Self;
+    /// The size of the type in bytes.
+    const BYTES: usize;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    fn exp(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+
+    /// Convert from f64, rounding to the nearest representable value.
+    fn from_f64(val: f64) -> Self;
+
+    /// Write the little-endian representation into 'buf'; panics unless
+    /// 'buf' is BYTES long.
+    fn write_le(self, buf: &mut [u8]);
+}
+
+macro_rules! impl_float {
+    ($t:ident) => {
+        impl Float for $t {
+            const ZERO: Self = 0.0;
+            const INFINITY: Self = $t::INFINITY;
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn sqrt(self) -> Self {
+                $t::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                $t::abs(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                $t::max(self, other)
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                $t::total_cmp(self, other)
+            }
+
+            fn exp(self) -> Self {
+                $t::exp(self)
+            }
+
+            fn powi(self, n: i32) -> Self {
+                $t::powi(self, n)
+            }
+
+            fn from_f64(val: f64) -> Self {
+                val as $t
+            }
+
+            fn write_le(self, buf: &mut [u8]) {
+                buf.copy_from_slice(&self.to_le_bytes())
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hypot<F: Float>(a: F, b: F) -> F {
+        (a * a + b * b).sqrt()
+    }
+
+    #[test]
+    fn test_float() {
+        assert_eq!(hypot(3.0f32, 4.0f32), 5.0f32);
+        assert_eq!(hypot(3.0f64, 4.0f64), 5.0f64);
+        assert_eq!(f32::from_f64(0.1), 0.1f32);
+        assert_eq!(Float::powi(2.0f64, 3), 8.0);
+        assert_eq!(Float::exp(0.0f32), 1.0);
+        assert_eq!(Float::total_cmp(&1.0f32, &f32::INFINITY), Ordering::Less);
+
+        let mut buf = [0u8; 4];
+        1.5f32.write_le(&mut buf);
+        assert_eq!(f32::from_le_bytes(buf), 1.5);
+    }
+}
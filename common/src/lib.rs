@@ -1,6 +1,9 @@
 // src/common/src/lib.rs
 //! Matrix operations.
+pub mod condensed;
 pub mod dense;
+pub mod edge_list;
+pub mod float;
 pub mod sparse;
 
 /// A trait with basic operations for an `m x n` Matrix.
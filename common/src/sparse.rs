@@ -28,7 +28,15 @@ impl<T> Matrix<T> for SparseMatrix<T> {
     }
 }
 
-// TODO: Implement an Iterator, swap operations?
+impl<T> SparseMatrix<T> {
+    /// Iterate over the set entries, ((row, col), value), in no particular
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.2.iter().map(|(k, v)| (*k, v))
+    }
+}
+
+// TODO: Swap operations?
 
 #[cfg(test)]
 mod tests {
@@ -43,4 +51,15 @@ mod tests {
         assert_eq!(dm.get(0, 0), Some(&true));
         assert_eq!(dm.get(1, 1), None);
     }
+
+    #[test]
+    fn test_sparse_matrix_iter() {
+        let mut dm = SparseMatrix::<usize>::new(10, 10);
+        dm.set(0, 1, 2);
+        dm.set(3, 4, 5);
+
+        let mut entries: Vec<((usize, usize), &usize)> = dm.iter().collect();
+        entries.sort();
+        assert_eq!(entries, vec![((0, 1), &2), ((3, 4), &5)]);
+    }
 }
@@ -0,0 +1,115 @@
+// src/complex/benches/trie.rs
+//! Benchmarking the storage backends against one another through the
+//! `Storage` trait; each is filled with the simplices of a Vietoris-Rips
+//! complex.
+#![feature(test)]
+extern crate test;
+use common::{sparse::SparseMatrix, Matrix};
+use complex::vietoris_rips::VietorisRips;
+use complex::{simplex_trie, simplex_trie_arena, simplex_trie_cycle, simplex_vec};
+use complex::{Simplex, SimplicialComplex, Storage};
+use rand::prelude::*;
+use test::Bencher;
+
+// Produce the simplices of a random, sparse flag complex; each vertex has
+// roughly ten neighbors.
+fn simplices() -> Vec<Simplex> {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut sm = SparseMatrix::new(500, 500);
+    for c in 0..500 {
+        for r in 0..c {
+            if rng.gen_bool(0.02) {
+                sm.set(c, r, true);
+                sm.set(r, c, true);
+            }
+        }
+    }
+
+    let complex: SimplicialComplex = VietorisRips::compute(None, &sm, 4, 0.0);
+    complex.into_iter().collect()
+}
+
+// Fill a backend with the simplices given.
+fn fill<S: Storage>(simplices: &[Simplex]) -> S {
+    let mut st = S::default();
+    for simplex in simplices {
+        st.add_simplex(simplex);
+    }
+    st
+}
+
+fn add<S: Storage>(b: &mut Bencher) {
+    let simplices = simplices();
+    b.iter(|| fill::<S>(&simplices))
+}
+
+fn contains<S: Storage>(b: &mut Bencher) {
+    let simplices = simplices();
+    let st: S = fill(&simplices);
+    b.iter(|| simplices.iter().all(|s| st.contains_simplex(s)))
+}
+
+fn cofaces<S: Storage>(b: &mut Bencher) {
+    let st: S = fill(&simplices());
+    let vertices: Vec<Simplex> = st.iter_dim(0).take(50).collect();
+    b.iter(|| vertices.iter().map(|v| st.cofaces(v).len()).sum::<usize>())
+}
+
+fn iter_dim<S: Storage>(b: &mut Bencher) {
+    let st: S = fill(&simplices());
+    b.iter(|| st.iter_dim(2).count())
+}
+
+// Each backend is benchmarked through the common `Storage` trait.
+macro_rules! bench_storage {
+    ($add:ident, $contains:ident, $cofaces:ident, $iter_dim:ident, $storage:path) => {
+        #[bench]
+        fn $add(b: &mut Bencher) {
+            add::<$storage>(b)
+        }
+
+        #[bench]
+        fn $contains(b: &mut Bencher) {
+            contains::<$storage>(b)
+        }
+
+        #[bench]
+        fn $cofaces(b: &mut Bencher) {
+            cofaces::<$storage>(b)
+        }
+
+        #[bench]
+        fn $iter_dim(b: &mut Bencher) {
+            iter_dim::<$storage>(b)
+        }
+    };
+}
+
+bench_storage!(
+    bench_btree_add,
+    bench_btree_contains,
+    bench_btree_cofaces,
+    bench_btree_iter_dim,
+    simplex_trie::SimplexTrie
+);
+bench_storage!(
+    bench_arena_add,
+    bench_arena_contains,
+    bench_arena_cofaces,
+    bench_arena_iter_dim,
+    simplex_trie_arena::SimplexTrie
+);
+bench_storage!(
+    bench_cycle_add,
+    bench_cycle_contains,
+    bench_cycle_cofaces,
+    bench_cycle_iter_dim,
+    simplex_trie_cycle::SimplexTrie
+);
+bench_storage!(
+    bench_vec_add,
+    bench_vec_contains,
+    bench_vec_cofaces,
+    bench_vec_iter_dim,
+    simplex_vec::SimplexVec
+);
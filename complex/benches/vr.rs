@@ -1,18 +1,24 @@
 // src/complex/benches/vr.rs
-//! Benchmarking various Vietoris-Rips algorithms.
+//! Benchmarking various Vietoris-Rips algorithms, and the storage backends
+//! a complex may be held within.
 #![feature(test)]
 extern crate test;
 use common::{sparse::SparseMatrix, Matrix};
 use complex::vietoris_rips::{VRAlgorithm, VietorisRips};
+use complex::{simplex_trie, simplex_trie_arena, simplex_trie_cycle, simplex_vec};
+use rand::prelude::*;
 use test::Bencher;
 
-// Produce an adjacency matrix suitable for testing.
+// Produce a random, sparse adjacency matrix suitable for testing; each vertex
+// has roughly ten neighbors.
 fn adj() -> SparseMatrix<bool> {
+    let mut rng = StdRng::seed_from_u64(7);
     let mut sm = SparseMatrix::new(500, 500);
     for c in 0..500 {
-        for r in 0..500 {
-            if (c * r) + 17 % 2 == 0 {
-                sm.set(c, r, true)
+        for r in 0..c {
+            if rng.gen_bool(0.02) {
+                sm.set(c, r, true);
+                sm.set(r, c, true);
             }
         }
     }
@@ -23,5 +29,76 @@ fn adj() -> SparseMatrix<bool> {
 #[bench]
 fn bench_inductive(b: &mut Bencher) {
     let adj_mat = adj();
-    b.iter(|| VietorisRips::compute(Some(VRAlgorithm::Inductive), &adj_mat, 5, 0))
+    b.iter(|| {
+        VietorisRips::<simplex_trie::SimplexTrie>::compute(
+            Some(VRAlgorithm::Inductive),
+            &adj_mat,
+            5,
+            0.0,
+        )
+    })
+}
+
+#[bench]
+fn bench_incremental(b: &mut Bencher) {
+    let adj_mat = adj();
+    b.iter(|| {
+        VietorisRips::<simplex_trie::SimplexTrie>::compute(
+            Some(VRAlgorithm::Incremental),
+            &adj_mat,
+            5,
+            0.0,
+        )
+    })
+}
+
+#[bench]
+fn bench_maximal(b: &mut Bencher) {
+    let adj_mat = adj();
+    b.iter(|| {
+        VietorisRips::<simplex_trie::SimplexTrie>::compute(
+            Some(VRAlgorithm::Maximal),
+            &adj_mat,
+            5,
+            0.0,
+        )
+    })
+}
+
+#[cfg(feature = "parallel")]
+#[bench]
+fn bench_parallel(b: &mut Bencher) {
+    let adj_mat = adj();
+    b.iter(|| {
+        VietorisRips::<simplex_trie::SimplexTrie>::compute(
+            Some(VRAlgorithm::Parallel),
+            &adj_mat,
+            5,
+            0.0,
+        )
+    })
+}
+
+#[bench]
+fn bench_storage_btree(b: &mut Bencher) {
+    let adj_mat = adj();
+    b.iter(|| VietorisRips::<simplex_trie::SimplexTrie>::compute(None, &adj_mat, 5, 0.0))
+}
+
+#[bench]
+fn bench_storage_arena(b: &mut Bencher) {
+    let adj_mat = adj();
+    b.iter(|| VietorisRips::<simplex_trie_arena::SimplexTrie>::compute(None, &adj_mat, 5, 0.0))
+}
+
+#[bench]
+fn bench_storage_cycle(b: &mut Bencher) {
+    let adj_mat = adj();
+    b.iter(|| VietorisRips::<simplex_trie_cycle::SimplexTrie>::compute(None, &adj_mat, 5, 0.0))
+}
+
+#[bench]
+fn bench_storage_vec(b: &mut Bencher) {
+    let adj_mat = adj();
+    b.iter(|| VietorisRips::<simplex_vec::SimplexVec>::compute(None, &adj_mat, 5, 0.0))
 }
@@ -0,0 +1,115 @@
+// src/complex/src/builder.rs
+//! Building a SimplicialComplex from an explicit listing of its simplices,
+//! such as a triangulated mesh or the output of another library; every face
+//! of each simplex given is filled in.
+use crate::closure::ComplexError;
+use crate::simplex::{Simplex, Vertex};
+use crate::simplex_trie::SimplexTrie;
+use crate::{SimplicialComplex, Storage};
+use std::marker::PhantomData;
+
+/// A builder of a SimplicialComplex held within the storage backend 'S'.
+pub struct ComplexBuilder<S = SimplexTrie> {
+    simplices: Vec<Simplex>,
+    storage: PhantomData<S>,
+}
+
+impl<S: Storage> Default for ComplexBuilder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Storage> ComplexBuilder<S> {
+    /// Create a new, empty ComplexBuilder.
+    pub fn new() -> Self {
+        ComplexBuilder {
+            simplices: vec![],
+            storage: PhantomData,
+        }
+    }
+
+    /// Add the simplex on the vertices 'ids', entering the filtration at
+    /// 'value', or zero. A face already given keeps the earlier of its
+    /// values. Returns `ComplexError::Empty` if 'ids' is empty.
+    pub fn simplex(mut self, ids: &[usize], value: Option<f64>) -> Result<Self, ComplexError> {
+        if ids.is_empty() {
+            return Err(ComplexError::Empty);
+        }
+
+        let value = value.unwrap_or(0.0);
+        let vertices = ids.iter().map(|i| Vertex::new(*i, value)).collect();
+        self.simplices.push(Simplex::new(vertices));
+        Ok(self)
+    }
+
+    /// Add each maximal simplex of an unfiltered complex, given by its
+    /// vertices; every simplex enters at zero. Returns `ComplexError::Empty`
+    /// if any is empty.
+    pub fn maximal<I, T>(self, simplices: I) -> Result<Self, ComplexError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[usize]>,
+    {
+        simplices
+            .into_iter()
+            .try_fold(self, |b, ids| b.simplex(ids.as_ref(), None))
+    }
+
+    /// Build the SimplicialComplex of the simplices given and their faces.
+    pub fn build(self) -> SimplicialComplex<S> {
+        SimplicialComplex::from_simplices(self.simplices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex_vec::SimplexVec;
+
+    #[test]
+    fn test_builder_maximal() {
+        // Two triangles sharing an edge, and a pendant edge.
+        let complex: SimplicialComplex = ComplexBuilder::new()
+            .maximal([[0, 1, 2], [1, 2, 3]])
+            .unwrap()
+            .maximal([vec![3, 4]])
+            .unwrap()
+            .build();
+        assert_eq!(complex.count_dim(0), 5);
+        assert_eq!(complex.count_dim(1), 6);
+        assert_eq!(complex.count_dim(2), 2);
+        assert_eq!(complex.dim(), 3);
+        assert_eq!(complex.validate(), Ok(()));
+        assert!(complex.into_iter().all(|s| s.weight() == 0.0));
+    }
+
+    #[test]
+    fn test_builder_filtration() {
+        let complex: SimplicialComplex<SimplexVec> = ComplexBuilder::new()
+            .simplex(&[0, 1], Some(1.0))
+            .and_then(|b| b.simplex(&[1, 2], Some(2.0)))
+            .and_then(|b| b.simplex(&[0, 1, 2], Some(3.0)))
+            .and_then(|b| b.simplex(&[5], Some(0.5)))
+            .unwrap()
+            .build();
+        assert_eq!(complex.count(), 8);
+        assert_eq!(complex.validate(), Ok(()));
+
+        // Each edge keeps its own value; the third enters with the triangle.
+        let weights: Vec<f64> = complex.iter_dim(1).map(|s| s.weight()).collect();
+        assert_eq!(weights.iter().filter(|w| **w == 3.0).count(), 1);
+        assert_eq!(complex.iter_dim(2).next().unwrap().weight(), 3.0);
+        assert_eq!(complex.sublevel(1.0).count(), 4);
+    }
+
+    #[test]
+    fn test_builder_empty() {
+        let builder: ComplexBuilder = ComplexBuilder::new();
+        assert_eq!(builder.simplex(&[], None).err(), Some(ComplexError::Empty));
+
+        let builder: ComplexBuilder = ComplexBuilder::new();
+        let maximal: [&[usize]; 2] = [&[0, 1], &[]];
+        assert_eq!(builder.maximal(maximal).err(), Some(ComplexError::Empty));
+    }
+}
@@ -0,0 +1,262 @@
+// src/complex/src/closure.rs
+//! Building a SimplicialComplex closed under faces, and validating one. A
+//! valid complex holds every face of each of its simplices, entering the
+//! filtration no later than the simplex itself; the vertices of a simplex
+//! are ordered by decreasing id, each weighted by the prefix it completes,
+//! so that identical simplices share a path.
+use crate::simplex::{Simplex, Vertex};
+use crate::{SimplicialComplex, Storage};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A way in which a SimplicialComplex fails to be a valid filtered complex.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ComplexError {
+    /// A simplex was given without any vertices.
+    Empty,
+    /// The vertices of a simplex are not ordered by decreasing id, or their
+    /// weights decrease along it; a repeated vertex is out of order too.
+    Unordered(Simplex),
+    /// A simplex is held more than once, under different weights.
+    Duplicate(Simplex),
+    /// A face of a simplex, given by its ids, is not held.
+    MissingFace { simplex: Simplex, face: Vec<usize> },
+    /// A face enters the filtration after one of its cofaces.
+    NotMonotone { face: Simplex, coface: Simplex },
+}
+
+impl fmt::Display for ComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ComplexError::Empty => write!(f, "a simplex must have at least one vertex"),
+            ComplexError::Unordered(simplex) => {
+                write!(f, "the vertices of {} are out of order", simplex)
+            }
+            ComplexError::Duplicate(simplex) => write!(f, "{} is held more than once", simplex),
+            ComplexError::MissingFace { simplex, face } => {
+                write!(f, "the face {:?} of {} is missing", face, simplex)
+            }
+            ComplexError::NotMonotone { face, coface } => {
+                write!(f, "the face {} enters after its coface {}", face, coface)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ComplexError {}
+
+/// Return the ids of a simplex, ordered.
+fn sorted_ids(simplex: &Simplex) -> Vec<usize> {
+    let mut ids: Vec<usize> = simplex.into_iter().map(|v| v.id()).collect();
+    ids.sort_unstable();
+    ids
+}
+
+impl<S: Storage> SimplicialComplex<S> {
+    /// Build a SimplicialComplex from 'simplices' along with every one of
+    /// their faces. A face enters with the earliest simplex containing it,
+    /// so the filtration is monotone whatever the weights given; repeated
+    /// vertices are dropped, and the vertices of each are reordered; a
+    /// simplex without vertices is skipped. Faces are generated a dimension
+    /// at a time, each facet of a face at most once; a simplex on k vertices
+    /// still has 2^k - 1 faces, so only small simplices are practical.
+    pub fn from_simplices<I: IntoIterator<Item = Simplex>>(simplices: I) -> Self {
+        // The filtration value of every face, by its ordered ids, within the
+        // level of its number of vertices.
+        let mut levels: Vec<HashMap<Vec<usize>, f64>> = vec![];
+        for simplex in simplices {
+            let mut ids = sorted_ids(&simplex);
+            ids.dedup();
+            if ids.is_empty() {
+                continue;
+            }
+
+            if levels.len() < ids.len() {
+                levels.resize_with(ids.len(), HashMap::new);
+            }
+
+            let weight = simplex.weight();
+            let value = levels[ids.len() - 1].entry(ids).or_insert(weight);
+            *value = value.min(weight);
+        }
+
+        // Each face passes its value down to its facets, from the highest
+        // dimension.
+        for len in (2..=levels.len()).rev() {
+            let (lower, upper) = levels.split_at_mut(len - 1);
+            for (ids, weight) in &upper[0] {
+                for k in 0..len {
+                    let facet = [&ids[..k], &ids[k + 1..]].concat();
+                    let value = lower[len - 2].entry(facet).or_insert(*weight);
+                    *value = value.min(*weight);
+                }
+            }
+        }
+
+        // Each vertex is weighted by the prefix it completes; those of
+        // greater id, as the vertices are ordered by decreasing id.
+        let mut storage = S::default();
+        for ids in levels.iter().flat_map(|level| level.keys()) {
+            let vertices: Vec<Vertex> = (0..ids.len())
+                .rev()
+                .map(|k| Vertex::new(ids[k], levels[ids.len() - k - 1][&ids[k..]]))
+                .collect();
+            storage.add_simplex(&Simplex::new(vertices));
+        }
+
+        let dim = levels.len();
+
+        SimplicialComplex(storage, dim)
+    }
+
+    /// Check that the SimplicialComplex is closed under faces, that the
+    /// vertices of each simplex are ordered, and that no face enters after
+    /// its cofaces; returning each error found.
+    pub fn validate(&self) -> Result<(), Vec<ComplexError>> {
+        let mut errors = vec![];
+        let mut simplices: HashMap<Vec<usize>, Simplex> = HashMap::new();
+        for simplex in self {
+            let ordered = simplex
+                .vertices()
+                .windows(2)
+                .all(|w| w[0].id() > w[1].id() && w[0].weight() <= w[1].weight());
+            if !ordered {
+                errors.push(ComplexError::Unordered(simplex.clone()));
+            }
+
+            if let Some(other) = simplices.insert(sorted_ids(&simplex), simplex) {
+                errors.push(ComplexError::Duplicate(other));
+            }
+        }
+
+        for (ids, simplex) in &simplices {
+            if ids.len() < 2 {
+                continue;
+            }
+
+            for k in 0..ids.len() {
+                let face: Vec<usize> = [&ids[..k], &ids[k + 1..]].concat();
+                match simplices.get(&face) {
+                    Some(f) if f.weight() > simplex.weight() => {
+                        errors.push(ComplexError::NotMonotone {
+                            face: f.clone(),
+                            coface: simplex.clone(),
+                        })
+                    }
+                    Some(_) => (),
+                    None => errors.push(ComplexError::MissingFace {
+                        simplex: simplex.clone(),
+                        face,
+                    }),
+                }
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex_trie::SimplexTrie;
+    use crate::vietoris_rips::VietorisRips;
+    use common::{sparse::SparseMatrix, Matrix};
+
+    #[test]
+    fn test_from_simplices() {
+        // A triangle given with its vertices unordered, and an edge entering
+        // before it.
+        let v = Vertex::new;
+        let complex: SimplicialComplex = SimplicialComplex::from_simplices(vec![
+            Simplex::new(vec![v(0, 0.0), v(2, 1.0), v(1, 2.0)]),
+            Simplex::new(vec![v(3, 0.0), v(2, 0.5)]),
+        ]);
+        assert_eq!(complex.count(), 9);
+        assert_eq!(complex.dim(), 3);
+        assert_eq!(complex.validate(), Ok(()));
+
+        let triangle = complex.locate(&[0, 1, 2]).unwrap();
+        assert_eq!(triangle.vertices(), &[v(2, 0.5), v(1, 2.0), v(0, 2.0)]);
+        assert_eq!(complex.locate(&[2, 3]).unwrap().weight(), 0.5);
+
+        // A face given after its coface enters with it.
+        let complex: SimplicialComplex = SimplicialComplex::from_simplices(vec![
+            Simplex::new(vec![v(1, 0.0), v(0, 1.0)]),
+            Simplex::new(vec![v(1, 0.0), v(0, 1.0), v(1, 1.0)]),
+            Simplex::new(vec![v(0, 3.0)]),
+        ]);
+        assert_eq!(complex.count(), 3);
+        assert_eq!(complex.locate(&[0]).unwrap().weight(), 1.0);
+        assert_eq!(complex.validate(), Ok(()));
+
+        // A simplex without vertices is skipped.
+        let complex: SimplicialComplex = SimplicialComplex::from_simplices(vec![
+            Simplex::new(vec![]),
+            Simplex::new(vec![v(1, 0.0), v(0, 1.0)]),
+        ]);
+        assert_eq!(complex.count(), 3);
+        assert_eq!(complex.dim(), 2);
+
+        // Two tetrahedra sharing an edge; the edge and its vertices enter
+        // with the earlier, through each dimension between.
+        let tetrahedron = |ids: [usize; 4], w: f64| -> Simplex {
+            Simplex::new(ids.iter().map(|i| v(*i, w)).collect())
+        };
+        let complex: SimplicialComplex = SimplicialComplex::from_simplices(vec![
+            tetrahedron([0, 1, 2, 3], 2.0),
+            tetrahedron([2, 3, 4, 5], 1.0),
+        ]);
+        assert_eq!(complex.count(), 27);
+        assert_eq!(complex.validate(), Ok(()));
+        assert_eq!(complex.locate(&[2, 3]).unwrap().weight(), 1.0);
+        assert_eq!(complex.locate(&[1, 2, 3]).unwrap().weight(), 2.0);
+        assert_eq!(
+            complex.locate(&[0, 2]).unwrap().vertices(),
+            &[v(2, 1.0), v(0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(4, 4);
+        for (a, b, len) in [(1, 0, 1.0), (2, 0, 2.0), (2, 1, 3.0), (3, 2, 0.5)] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+        let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 3);
+        assert_eq!(complex.validate(), Ok(()));
+
+        // A triangle inserted without its faces.
+        let v = Vertex::new;
+        let mut trie = SimplexTrie::new();
+        let triangle = Simplex::new(vec![v(2, 0.0), v(1, 1.0), v(0, 1.0)]);
+        trie.add_simplex(&triangle);
+        let errors = SimplicialComplex(trie, 3).validate().unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.contains(&ComplexError::MissingFace {
+            simplex: triangle,
+            face: vec![0, 2],
+        }));
+
+        // Vertices out of order, and a vertex entering after its edge.
+        let mut trie = SimplexTrie::new();
+        let edge = Simplex::new(vec![v(0, 0.0), v(1, 1.0)]);
+        trie.add_simplex(&edge);
+        trie.add_simplex(&Simplex::new(vec![v(1, 2.0)]));
+        let errors = SimplicialComplex(trie, 2).validate().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                ComplexError::Unordered(edge.clone()),
+                ComplexError::NotMonotone {
+                    face: Simplex::new(vec![v(1, 2.0)]),
+                    coface: edge,
+                }
+            ]
+        );
+    }
+}
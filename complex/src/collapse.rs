@@ -0,0 +1,251 @@
+// src/complex/src/collapse.rs
+//! Shrinking filtrations without changing their persistent homology.
+//! A simplex whose link is a cone, i.e. every maximal simplex containing it
+//! shares some other vertex, may be removed along with its cofaces without
+//! changing the homotopy type. A simplex is only removed if this holds at
+//! every filtration value from its entry onward, with the removals before it
+//! already made; every complex of the filtration is then homotopy equivalent
+//! to its collapse through the inclusion, so the persistence diagram is
+//! unchanged.
+//! Referenced from: 'Edge Collapse and Persistence of Flag Complexes - Jean-Daniel Boissonnat · Siddharth Pritam'
+//! Referenced from: 'Strong Collapse for Persistence - Jean-Daniel Boissonnat · Siddharth Pritam · Divyansh Pareek'
+use crate::vietoris_rips::Edge;
+use crate::{filtration, SimplicialComplex, Storage};
+use common::{sparse::SparseMatrix, Matrix};
+use std::collections::{HashMap, HashSet};
+
+/// Return the distinct values of 'values' at or above 'from', ascending.
+fn steps<I: Iterator<Item = f64>>(values: I, from: f64) -> Vec<f64> {
+    let mut r: Vec<f64> = values.filter(|t| *t >= from).collect();
+    r.sort_by(f64::total_cmp);
+    r.dedup();
+    r
+}
+
+/// Return whether the edge (a, b) is dominated within 'edges'; some other
+/// vertex is adjacent to every vertex adjacent to both a and b.
+fn edge_dominated(a: usize, b: usize, verts: &[usize], edges: &HashSet<(usize, usize)>) -> bool {
+    let adjacent = |x: usize, y: usize| x == y || edges.contains(&(x.max(y), x.min(y)));
+    let common: Vec<usize> = verts
+        .iter()
+        .copied()
+        .filter(|x| adjacent(*x, a) && adjacent(*x, b))
+        .collect();
+
+    common
+        .iter()
+        .filter(|v| **v != a && **v != b)
+        .any(|v| common.iter().all(|x| adjacent(*v, *x)))
+}
+
+/// Collapse the edges of a filtered graph, such as the weighted adjacency
+/// matrix given to `VietorisRips::compute_weighted`; the flag filtration of
+/// the result has the persistence diagram of the original. Edges are
+/// considered from the last to enter, and kept at their original length.
+/// As Vietoris-Rips complexes are truncated at a dimension, only homology
+/// below the top dimension computed is preserved.
+pub fn edge_collapse<T: Edge, M: Matrix<T>>(adj: &M) -> SparseMatrix<f64> {
+    let (rows, _) = adj.dim();
+
+    // Each edge (a, b), a > b, in filtration order.
+    let mut edges: Vec<(f64, usize, usize)> = vec![];
+    let mut nbrs: Vec<Vec<usize>> = vec![vec![]; rows];
+    for a in 0..rows {
+        for b in 0..a {
+            if let Some(len) = adj.get(a, b).and_then(|val| val.length()) {
+                edges.push((len, a, b));
+                nbrs[a].push(b);
+                nbrs[b].push(a);
+            }
+        }
+    }
+    edges.sort_by(|x, y| x.0.total_cmp(&y.0).then((x.1, x.2).cmp(&(y.1, y.2))));
+    let lengths: HashMap<(usize, usize), f64> =
+        edges.iter().map(|(len, a, b)| ((*a, *b), *len)).collect();
+
+    let mut removed: HashSet<(usize, usize)> = HashSet::new();
+    for (len, a, b) in edges.iter().rev() {
+        // Only edges between a, b and their common neighbors can change
+        // whether (a, b) is dominated.
+        let around: HashSet<usize> = nbrs[*b].iter().copied().collect();
+        let mut verts: Vec<usize> = nbrs[*a]
+            .iter()
+            .filter(|x| around.contains(x))
+            .copied()
+            .collect();
+        verts.extend([*a, *b]);
+
+        let within: HashSet<usize> = verts.iter().copied().collect();
+        let local: Vec<(f64, usize, usize)> = verts
+            .iter()
+            .flat_map(|x| {
+                nbrs[*x]
+                    .iter()
+                    .filter(|y| *y < x && within.contains(y))
+                    .map(move |y| (*x, *y))
+            })
+            .filter(|e| !removed.contains(e))
+            .map(|(x, y)| (lengths[&(x, y)], x, y))
+            .collect();
+
+        let dominated = steps(local.iter().map(|e| e.0), *len).iter().all(|t| {
+            let present: HashSet<(usize, usize)> = local
+                .iter()
+                .filter(|e| e.0 <= *t)
+                .map(|e| (e.1, e.2))
+                .collect();
+            edge_dominated(*a, *b, &verts, &present)
+        });
+
+        if dominated {
+            removed.insert((*a, *b));
+        }
+    }
+
+    let mut sm = SparseMatrix::new(rows, rows);
+    for (len, a, b) in edges.iter().filter(|e| !removed.contains(&(e.1, e.2))) {
+        sm.set(*a, *b, *len);
+        sm.set(*b, *a, *len);
+    }
+
+    sm
+}
+
+/// Return whether the ids 'a', ordered by id, are a subset of 'b'.
+fn subset(a: &[usize], b: &[usize]) -> bool {
+    a.iter().all(|x| b.binary_search(x).is_ok())
+}
+
+/// Strongly collapse a filtered SimplicialComplex; the result has the
+/// persistence diagram of the original. A vertex is removed when it is
+/// dominated by another, and more generally any simplex whose link remains
+/// a cone; simplices are considered from the last to enter. Within a
+/// filtration a vertex is never dominated as it enters, so on the
+/// filtrations of Vietoris-Rips complexes edges and their cofaces are
+/// removed instead; an unfiltered complex, with every weight equal,
+/// collapses to its core. Little is removed from a Vietoris-Rips complex
+/// truncated below its largest cliques, whose links are rarely cones.
+pub fn strong_collapse<S: Storage>(complex: &SimplicialComplex<S>) -> SimplicialComplex<S> {
+    let simplices = filtration(complex);
+
+    // The simplices containing each vertex.
+    let mut star: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, (ids, _)) in simplices.iter().enumerate() {
+        for v in ids {
+            star.entry(*v).or_default().push(i);
+        }
+    }
+
+    let mut gone = vec![false; simplices.len()];
+    for i in (0..simplices.len()).rev() {
+        if gone[i] {
+            continue;
+        }
+
+        let (ids, simplex) = &simplices[i];
+        let cofaces: Vec<usize> = star[&ids[0]]
+            .iter()
+            .copied()
+            .filter(|j| !gone[*j] && subset(ids, &simplices[*j].0))
+            .collect();
+
+        let dominated = steps(
+            cofaces.iter().map(|j| simplices[*j].1.weight()),
+            simplex.weight(),
+        )
+        .iter()
+        .all(|t| {
+            let present: Vec<&Vec<usize>> = cofaces
+                .iter()
+                .filter(|j| simplices[**j].1.weight() <= *t)
+                .map(|j| &simplices[*j].0)
+                .collect();
+            let maximal: Vec<&&Vec<usize>> = present
+                .iter()
+                .filter(|s| !present.iter().any(|o| o.len() > s.len() && subset(s, o)))
+                .collect();
+
+            // Some vertex outside the simplex lies in every maximal
+            // coface. The simplex is among its own cofaces, present from
+            // its entry, so at least one coface is maximal.
+            maximal[0]
+                .iter()
+                .filter(|v| !ids.contains(v))
+                .any(|v| maximal.iter().all(|m| m.contains(v)))
+        });
+
+        if dominated {
+            for j in cofaces {
+                gone[j] = true;
+            }
+        }
+    }
+
+    let mut storage = S::default();
+    for (_, simplex) in simplices
+        .iter()
+        .zip(&gone)
+        .filter(|(_, g)| !**g)
+        .map(|(s, _)| s)
+    {
+        storage.add_simplex(simplex);
+    }
+
+    SimplicialComplex(storage, complex.dim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vietoris_rips::VietorisRips;
+
+    #[test]
+    fn test_edge_collapse() {
+        // A square with a single diagonal; the diagonal enters last, filling
+        // the square, and is dominated by either endpoint of the other.
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(4, 4);
+        for (a, b, len) in [
+            (1, 0, 1.0),
+            (2, 1, 1.0),
+            (3, 2, 1.0),
+            (3, 0, 1.0),
+            (2, 0, 2.0),
+        ] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        // The diagonal only kills the loop born at 1.0; it isn't dominated
+        // as it enters, as the square is not yet filled.
+        let collapsed = edge_collapse(&adj);
+        assert_eq!(collapsed.get(2, 0), Some(&2.0));
+
+        // Filling the square at once, it collapses to a tree.
+        adj.set(2, 0, 1.0);
+        adj.set(0, 2, 1.0);
+        let collapsed = edge_collapse(&adj);
+        let kept: usize = (0..4)
+            .map(|a| (0..a).filter(|b| collapsed.get(a, *b).is_some()).count())
+            .sum();
+        assert_eq!(kept, 3);
+    }
+
+    #[test]
+    fn test_strong_collapse() {
+        // A filled triangle at a single value collapses to a vertex.
+        let mut adj: SparseMatrix<bool> = SparseMatrix::new(3, 3);
+        for (a, b) in [(1, 0), (2, 0), (2, 1)] {
+            adj.set(a, b, true);
+            adj.set(b, a, true);
+        }
+
+        let complex: SimplicialComplex = VietorisRips::compute(None, &adj, 3, 0.0);
+        let collapsed = strong_collapse(&complex);
+        assert_eq!(collapsed.into_iter().count(), 1);
+        assert_eq!(collapsed.dim(), 3);
+
+        // A hollow triangle is its own core.
+        let complex: SimplicialComplex = VietorisRips::compute(None, &adj, 2, 0.0);
+        assert_eq!(strong_collapse(&complex).into_iter().count(), 6);
+    }
+}
@@ -1,23 +1,238 @@
 // src/complex/src/lib.rs
 //! Fundamental utilities for computing and interacting with Simplices.
+pub mod builder;
+pub mod closure;
+pub mod collapse;
+pub mod morse;
+pub mod operations;
 mod simplex;
-mod simplex_trie;
-mod simplex_trie_arena;
+pub mod simplex_trie;
+pub mod simplex_trie_arena;
+pub mod simplex_trie_cycle;
+pub mod simplex_vec;
+pub mod sparse_rips;
+pub mod storage;
+pub mod triangulations;
 pub mod vietoris_rips;
 
-pub struct SimplicialComplex(simplex_trie::SimplexTrie, usize);
+pub use builder::ComplexBuilder;
+pub use closure::ComplexError;
+pub use simplex::{Simplex, Vertex};
+pub use storage::Storage;
 
-impl SimplicialComplex {
-    pub fn iter_dim(&self, sz: usize) -> simplex_trie::SimplexTrieIterator {
+/// A SimplicialComplex held within a storage backend, by default a
+/// `simplex_trie::SimplexTrie`, and the dimension it was computed up to.
+pub struct SimplicialComplex<S = simplex_trie::SimplexTrie>(S, usize);
+
+impl<S: Storage> SimplicialComplex<S> {
+    /// Return the dimension the SimplicialComplex was computed up to.
+    pub fn dim(&self) -> usize {
+        self.1
+    }
+
+    pub fn iter_dim(&self, sz: usize) -> S::Iter<'_> {
         self.0.iter_dim(sz)
     }
+
+    /// Return the number of simplices.
+    pub fn count(&self) -> usize {
+        self.0.count()
+    }
+
+    /// Return the number of simplices of dimension 'sz'.
+    pub fn count_dim(&self, sz: usize) -> usize {
+        self.0.count_dim(sz)
+    }
+
+    /// Return the Euler characteristic; the alternating sum of the number
+    /// of simplices of each dimension.
+    pub fn euler(&self) -> i64 {
+        (0..self.1)
+            .map(|k| match k % 2 {
+                0 => self.count_dim(k) as i64,
+                _ => -(self.count_dim(k) as i64),
+            })
+            .sum()
+    }
+
+    /// Return whether the complex holds a simplex.
+    pub fn contains(&self, simplex: &Simplex) -> bool {
+        self.0.contains_simplex(simplex)
+    }
+
+    /// Return the complex held within another backend.
+    pub fn convert<B: Storage>(&self) -> SimplicialComplex<B> {
+        self.subcomplex(|_| true, self.1)
+    }
+
+    /// Return the subcomplex of the simplices satisfying 'keep'; each face
+    /// of a kept simplex must be kept as well.
+    fn subcomplex<B: Storage, F>(&self, keep: F, dim: usize) -> SimplicialComplex<B>
+    where
+        F: Fn(&Simplex) -> bool,
+    {
+        let mut storage = B::default();
+        for simplex in self.0.iter().filter(|s| keep(s)) {
+            storage.add_simplex(&simplex);
+        }
+
+        SimplicialComplex(storage, dim)
+    }
+
+    /// Return the 'k'-skeleton; every simplex of dimension 'k' or below.
+    pub fn skeleton(&self, k: usize) -> SimplicialComplex<S> {
+        self.subcomplex(|s| s.dim() <= k, self.1.min(k + 1))
+    }
+
+    /// Return the subcomplex induced on 'vertices'; every simplex whose
+    /// vertices are all among them.
+    pub fn induced(&self, vertices: &[usize]) -> SimplicialComplex<S> {
+        self.subcomplex(
+            |s| s.into_iter().all(|v| vertices.contains(&v.id())),
+            self.1,
+        )
+    }
+
+    /// Return the sublevel set at 'value'; every simplex entering the
+    /// filtration at or before it.
+    pub fn sublevel(&self, value: f64) -> SimplicialComplex<S> {
+        self.subcomplex(|s| s.weight() <= value, self.1)
+    }
+
+    /// Remove a simplex along with its cofaces.
+    pub fn remove(&mut self, simplex: &Simplex) {
+        self.0.remove_simplex(simplex)
+    }
+
+    /// Return the simplex with exactly the vertices 'ids'.
+    pub fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        self.0.locate(ids)
+    }
+
+    /// Return the index of the simplex with exactly the vertices 'ids'
+    /// within its dimension. Within the default `SimplexTrie` it is given as
+    /// the simplex is inserted, and stable as others are.
+    pub fn index(&self, ids: &[usize]) -> Option<usize> {
+        self.0.index(ids)
+    }
+
+    /// Return the facets of a simplex.
+    pub fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.0.facets(simplex)
+    }
+
+    /// Return the proper cofaces of a simplex.
+    pub fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.0.cofaces(simplex)
+    }
+
+    /// Return the star of a simplex; the simplex and its cofaces.
+    pub fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.0.star(simplex)
+    }
+
+    /// Return the link of a simplex.
+    pub fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.0.link(simplex)
+    }
 }
 
-impl<'a> IntoIterator for &'a SimplicialComplex {
-    type Item = simplex::Simplex;
-    type IntoIter = simplex_trie::SimplexTrieIterator<'a>;
+/// Return each simplex of a SimplicialComplex with its ids ordered, sorted
+/// into filtration order; by weight, then dimension, then ids.
+pub(crate) fn filtration<S: Storage>(complex: &SimplicialComplex<S>) -> Vec<(Vec<usize>, Simplex)> {
+    let mut r: Vec<(Vec<usize>, Simplex)> = complex
+        .into_iter()
+        .map(|s| {
+            let mut ids: Vec<usize> = (&s).into_iter().map(|v| v.id()).collect();
+            ids.sort_unstable();
+            (ids, s)
+        })
+        .collect();
+    r.sort_by(|x, y| {
+        x.1.weight()
+            .total_cmp(&y.1.weight())
+            .then(x.0.len().cmp(&y.0.len()))
+            .then(x.0.cmp(&y.0))
+    });
+
+    r
+}
+
+impl<'a, S: Storage> IntoIterator for &'a SimplicialComplex<S> {
+    type Item = Simplex;
+    type IntoIter = S::Iter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vietoris_rips::VietorisRips;
+    use common::{sparse::SparseMatrix, Matrix};
+
+    /// A square (0, 1, 2, 3) with its edges at 1.0 and a diagonal (0, 2) at
+    /// 2.0, and a pendant edge (3, 4) at 3.0.
+    fn square() -> SimplicialComplex {
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(5, 5);
+        for (a, b, len) in [
+            (1, 0, 1.0),
+            (2, 1, 1.0),
+            (3, 2, 1.0),
+            (3, 0, 1.0),
+            (2, 0, 2.0),
+            (4, 3, 3.0),
+        ] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        VietorisRips::compute_weighted(None, &adj, 3)
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut complex = square();
+        assert_eq!(complex.into_iter().count(), 13);
+
+        // The diagonal and both triangles across it.
+        complex.remove(&complex.locate(&[0, 2]).unwrap());
+        assert_eq!(complex.into_iter().count(), 10);
+        assert!(complex.locate(&[0, 1, 2]).is_none());
+    }
+
+    #[test]
+    fn test_skeleton() {
+        let complex = square();
+        let skel = complex.skeleton(1);
+        assert_eq!(skel.into_iter().count(), 11);
+        assert_eq!(skel.iter_dim(2).count(), 0);
+        assert_eq!(skel.dim(), 2);
+        assert_eq!(complex.skeleton(0).into_iter().count(), 5);
+    }
+
+    #[test]
+    fn test_induced() {
+        let complex = square();
+        let induced = complex.induced(&[0, 1, 2]);
+        assert_eq!(induced.into_iter().count(), 7);
+        assert!(induced.locate(&[0, 1, 2]).is_some());
+
+        // Dropping vertex 3 cuts off vertex 4.
+        let induced = complex.induced(&[0, 1, 2, 4]);
+        let vertex = induced.locate(&[4]).unwrap();
+        assert!(induced.cofaces(&vertex).is_empty());
+    }
+
+    #[test]
+    fn test_sublevel() {
+        let complex = square();
+        let sub = complex.sublevel(1.0);
+        assert_eq!(sub.into_iter().count(), 9);
+        assert!(sub.into_iter().all(|s| s.weight() <= 1.0));
+        assert_eq!(complex.sublevel(2.0).into_iter().count(), 12);
+        assert_eq!(complex.sublevel(-1.0).into_iter().count(), 0);
     }
 }
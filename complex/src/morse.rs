@@ -0,0 +1,237 @@
+// src/complex/src/morse.rs
+//! Discrete Morse theory on a filtered SimplicialComplex. A gradient vector
+//! field is an acyclic matching of simplices with their facets; the
+//! unmatched (critical) simplices span the Morse complex, a smaller chain
+//! complex with the same homology. Only simplices of equal weight are
+//! matched, so every subcomplex of the filtration is respected and the
+//! Morse complex has the persistence diagram of the original.
+//! Referenced from: 'Morse Theory for Cell Complexes - Robin Forman'
+//! Referenced from: 'Morse Theory for Filtrations and Efficient Computation of Persistent Homology - Konstantin Mischaikow · Vidit Nanda'
+use crate::simplex::Simplex;
+use crate::{filtration, SimplicialComplex, Storage};
+use std::collections::{BTreeSet, HashMap};
+
+/// A gradient vector field over the simplices of a SimplicialComplex, each
+/// in filtration order.
+pub struct GradientField {
+    cells: Vec<Simplex>,
+    facets: Vec<Vec<usize>>,
+    /// The simplex each is matched with, None if critical.
+    partner: Vec<Option<usize>>,
+    /// The order in which each simplex was removed; gradient paths only
+    /// lead to simplices removed later.
+    time: Vec<usize>,
+}
+
+impl GradientField {
+    /// Compute a gradient vector field by greedy elementary collapses; a free
+    /// face, with a single coface, is matched with it and both are removed.
+    /// Whenever no free face remains, the last simplex of the filtration
+    /// left is removed as critical.
+    pub fn new<S: Storage>(complex: &SimplicialComplex<S>) -> Self {
+        let simplices = filtration(complex);
+        let n = simplices.len();
+        let index: HashMap<&[usize], usize> = simplices
+            .iter()
+            .enumerate()
+            .map(|(i, (ids, _))| (ids.as_slice(), i))
+            .collect();
+
+        let mut facets: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut cofacets: Vec<Vec<usize>> = vec![vec![]; n];
+        for (i, (ids, _)) in simplices.iter().enumerate() {
+            if ids.len() > 1 {
+                for k in 0..ids.len() {
+                    let face: Vec<usize> = [&ids[..k], &ids[k + 1..]].concat();
+                    let j = index[face.as_slice()];
+                    facets[i].push(j);
+                    cofacets[j].push(i);
+                }
+            }
+        }
+
+        let cells: Vec<Simplex> = simplices.into_iter().map(|(_, s)| s).collect();
+        let mut partner = vec![None; n];
+        let mut time = vec![0; n];
+        let mut alive = vec![true; n];
+        let mut count: Vec<usize> = cofacets.iter().map(|c| c.len()).collect();
+        let mut free: Vec<usize> = (0..n).filter(|i| count[*i] == 1).collect();
+        let mut removed = 0;
+
+        let mut remove = |i: usize, alive: &mut Vec<bool>, free: &mut Vec<usize>| {
+            alive[i] = false;
+            time[i] = removed;
+            removed += 1;
+            for f in &facets[i] {
+                count[*f] -= 1;
+                if count[*f] == 1 {
+                    free.push(*f);
+                }
+            }
+        };
+
+        let mut last = n;
+        loop {
+            while let Some(s) = free.pop() {
+                let coface = match alive[s] {
+                    true => cofacets[s].iter().copied().filter(|c| alive[*c]).collect(),
+                    false => vec![],
+                };
+
+                if let [c] = coface[..] {
+                    if cells[c].weight() == cells[s].weight() {
+                        partner[s] = Some(c);
+                        partner[c] = Some(s);
+                        remove(c, &mut alive, &mut free);
+                        remove(s, &mut alive, &mut free);
+                    }
+                }
+            }
+
+            // The last simplex left has no cofaces left, as each sorts after
+            // it; it is critical.
+            match (0..last).rev().find(|i| alive[*i]) {
+                Some(i) => {
+                    remove(i, &mut alive, &mut free);
+                    last = i;
+                }
+                None => break,
+            }
+        }
+
+        GradientField {
+            cells,
+            facets,
+            partner,
+            time,
+        }
+    }
+
+    /// Return the indices of the critical simplices, in filtration order.
+    fn critical_indices(&self) -> Vec<usize> {
+        (0..self.cells.len())
+            .filter(|i| self.partner[*i].is_none())
+            .collect()
+    }
+
+    /// Return the critical simplices, in filtration order.
+    pub fn critical(&self) -> Vec<Simplex> {
+        self.critical_indices()
+            .into_iter()
+            .map(|i| self.cells[i].clone())
+            .collect()
+    }
+
+    /// Return each matched pair of a simplex with one of its cofacets.
+    pub fn pairs(&self) -> Vec<(Simplex, Simplex)> {
+        (0..self.cells.len())
+            .filter_map(|i| match self.partner[i] {
+                Some(c) if c > i => Some((self.cells[i].clone(), self.cells[c].clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Compute the boundary of the Morse complex mod 2; a column for each
+    /// critical simplex, holding the indices (into `critical`) of the
+    /// critical facets reached by an odd number of gradient paths.
+    pub fn morse_boundary(&self) -> Vec<Vec<usize>> {
+        let critical = self.critical_indices();
+        let position: HashMap<usize, usize> =
+            critical.iter().enumerate().map(|(p, i)| (*i, p)).collect();
+
+        critical
+            .iter()
+            .map(|i| {
+                // The chain, keyed by removal order so each simplex is
+                // visited once all paths into it have been followed.
+                let mut chain: BTreeSet<(usize, usize)> = BTreeSet::new();
+                let toggle = |chain: &mut BTreeSet<(usize, usize)>, f: usize| {
+                    if !chain.remove(&(self.time[f], f)) {
+                        chain.insert((self.time[f], f));
+                    }
+                };
+
+                for f in &self.facets[*i] {
+                    toggle(&mut chain, *f);
+                }
+
+                let mut col = vec![];
+                while let Some((_, s)) = chain.pop_first() {
+                    match self.partner[s] {
+                        None => col.push(position[&s]),
+                        Some(c) if self.cells[c].dim() > self.cells[s].dim() => {
+                            for f in self.facets[c].iter().filter(|f| **f != s) {
+                                toggle(&mut chain, *f);
+                            }
+                        }
+                        Some(_) => (),
+                    }
+                }
+
+                col.sort_unstable();
+                col
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vietoris_rips::VietorisRips;
+    use common::{sparse::SparseMatrix, Matrix};
+
+    fn triangle() -> SparseMatrix<bool> {
+        let mut adj: SparseMatrix<bool> = SparseMatrix::new(3, 3);
+        for (a, b) in [(1, 0), (2, 0), (2, 1)] {
+            adj.set(a, b, true);
+            adj.set(b, a, true);
+        }
+
+        adj
+    }
+
+    #[test]
+    fn test_gradient_field() {
+        // A filled triangle collapses to a single vertex.
+        let complex: SimplicialComplex = VietorisRips::compute(None, &triangle(), 3, 0.0);
+        let field = GradientField::new(&complex);
+        assert_eq!(field.critical().len(), 1);
+        assert_eq!(field.critical()[0].dim(), 0);
+        assert_eq!(field.pairs().len(), 3);
+        assert_eq!(field.morse_boundary(), vec![vec![]]);
+
+        // A hollow triangle keeps a vertex and an edge, the loop.
+        let complex: SimplicialComplex = VietorisRips::compute(None, &triangle(), 2, 0.0);
+        let field = GradientField::new(&complex);
+        let dims: Vec<usize> = field.critical().iter().map(|s| s.dim()).collect();
+        assert_eq!(dims, vec![0, 1]);
+        assert_eq!(field.morse_boundary(), vec![vec![], vec![]]);
+    }
+
+    #[test]
+    fn test_gradient_field_filtration() {
+        // Edges entering one at a time are never matched with a vertex, as
+        // each enters after both of its own; only the triangle and its
+        // last edge pair up.
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(3, 3);
+        for (a, b, len) in [(1, 0, 1.0), (2, 0, 2.0), (2, 1, 3.0)] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 3);
+        let field = GradientField::new(&complex);
+        assert_eq!(field.critical().len(), 5);
+        for (face, coface) in field.pairs() {
+            assert!(face.is_face(&coface));
+            assert_eq!(face.weight(), coface.weight());
+        }
+
+        // The two remaining edges each join a pair of components.
+        let boundary = field.morse_boundary();
+        assert_eq!(boundary[3], vec![0, 1]);
+        assert_eq!(boundary[4], vec![0, 2]);
+    }
+}
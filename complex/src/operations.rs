@@ -0,0 +1,227 @@
+// src/complex/src/operations.rs
+//! Topological constructions over SimplicialComplexes. The vertices of a
+//! result are relabelled; those of the first complex become 0..n in order
+//! of their ids, and those of a second follow. A simplex built from others
+//! enters the filtration with the last of them, so each construction is
+//! filtered as well.
+//! Referenced from: 'Elements of Algebraic Topology - James R. Munkres'
+use crate::simplex::{Simplex, Vertex};
+use crate::{SimplicialComplex, Storage};
+use std::collections::HashMap;
+
+/// Each simplex of a complex as its relabelled ids, ordered, and its weight,
+/// along with the number of vertices.
+fn relabel<S: Storage>(complex: &SimplicialComplex<S>) -> (Vec<(Vec<usize>, f64)>, usize) {
+    let mut vertices: Vec<usize> = complex.iter_dim(0).map(|s| s.vertices()[0].id()).collect();
+    vertices.sort_unstable();
+    let label: HashMap<usize, usize> = vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+
+    let simplices = complex
+        .into_iter()
+        .map(|s| {
+            let mut ids: Vec<usize> = (&s).into_iter().map(|v| label[&v.id()]).collect();
+            ids.sort_unstable();
+            (ids, s.weight())
+        })
+        .collect();
+
+    (simplices, vertices.len())
+}
+
+/// Return a simplex on 'ids', entering at 'weight'.
+fn simplex(ids: &[usize], weight: f64) -> Simplex {
+    Simplex::new(ids.iter().map(|i| Vertex::new(*i, weight)).collect())
+}
+
+/// Push each monotone path through the grid of 'p' by 'q' steps onto 'r',
+/// as the cells it visits; 'path' holds those visited so far.
+fn staircases(
+    p: usize,
+    q: usize,
+    path: &mut Vec<(usize, usize)>,
+    r: &mut Vec<Vec<(usize, usize)>>,
+) {
+    let (i, j) = *path.last().unwrap();
+    if i == p && j == q {
+        r.push(path.clone());
+        return;
+    }
+
+    for next in [(i + 1, j), (i, j + 1)] {
+        if next.0 <= p && next.1 <= q {
+            path.push(next);
+            staircases(p, q, path, r);
+            path.pop();
+        }
+    }
+}
+
+impl<S: Storage> SimplicialComplex<S> {
+    /// Return the cone; each simplex joined with a new apex, which enters at
+    /// zero.
+    pub fn cone(&self) -> Self {
+        let (simplices, n) = relabel(self);
+        let mut r = vec![simplex(&[n], 0.0)];
+        for (ids, weight) in simplices {
+            r.push(simplex(&[ids.as_slice(), &[n]].concat(), weight));
+        }
+
+        SimplicialComplex::from_simplices(r)
+    }
+
+    /// Return the suspension; the join with a pair of points, each entering
+    /// at zero.
+    pub fn suspension(&self) -> Self {
+        let (simplices, n) = relabel(self);
+        let mut r = vec![simplex(&[n], 0.0), simplex(&[n + 1], 0.0)];
+        for (ids, weight) in simplices {
+            r.push(simplex(&[ids.as_slice(), &[n]].concat(), weight));
+            r.push(simplex(&[ids.as_slice(), &[n + 1]].concat(), weight));
+        }
+
+        SimplicialComplex::from_simplices(r)
+    }
+
+    /// Return the disjoint union with another complex.
+    pub fn disjoint_union<B: Storage>(&self, other: &SimplicialComplex<B>) -> Self {
+        let (a, n) = relabel(self);
+        let (b, _) = relabel(other);
+        let r = a
+            .iter()
+            .map(|(ids, weight)| simplex(ids, *weight))
+            .chain(b.iter().map(|(ids, weight)| {
+                let ids: Vec<usize> = ids.iter().map(|i| i + n).collect();
+                simplex(&ids, *weight)
+            }));
+
+        SimplicialComplex::from_simplices(r)
+    }
+
+    /// Return the join with another complex; both complexes, and the union
+    /// of each pair of their simplices.
+    pub fn join<B: Storage>(&self, other: &SimplicialComplex<B>) -> Self {
+        let (a, n) = relabel(self);
+        let (b, _) = relabel(other);
+        let mut r: Vec<Simplex> = a
+            .iter()
+            .map(|(ids, weight)| simplex(ids, *weight))
+            .collect();
+        for (ids, weight) in &b {
+            let ids: Vec<usize> = ids.iter().map(|i| i + n).collect();
+            r.push(simplex(&ids, *weight));
+            for (x, w) in &a {
+                r.push(simplex(&[x.as_slice(), &ids].concat(), weight.max(*w)));
+            }
+        }
+
+        SimplicialComplex::from_simplices(r)
+    }
+
+    /// Return the product with another complex, by the staircase
+    /// triangulation; the vertex (x, y) has id 'x * m + y' for the 'm'
+    /// vertices of the other. Each pair of simplices spans a prism, split
+    /// into a simplex for each monotone path across the grid of their
+    /// vertices.
+    pub fn product<B: Storage>(&self, other: &SimplicialComplex<B>) -> Self {
+        let (a, _) = relabel(self);
+        let (b, m) = relabel(other);
+        let mut r = vec![];
+        for (x, wx) in &a {
+            for (y, wy) in &b {
+                let mut paths = vec![];
+                staircases(x.len() - 1, y.len() - 1, &mut vec![(0, 0)], &mut paths);
+                for path in paths {
+                    let ids: Vec<usize> = path.iter().map(|(i, j)| x[*i] * m + y[*j]).collect();
+                    r.push(simplex(&ids, wx.max(*wy)));
+                }
+            }
+        }
+
+        SimplicialComplex::from_simplices(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::triangulations::*;
+    use crate::{ComplexBuilder, SimplicialComplex};
+
+    #[test]
+    fn test_staircases() {
+        let mut paths = vec![];
+        super::staircases(1, 2, &mut vec![(0, 0)], &mut paths);
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().all(|p| p.len() == 4 && p[3] == (1, 2)));
+    }
+
+    #[test]
+    fn test_operations_euler() {
+        let circle = sphere(1).complex;
+        let points = sphere(0).complex;
+        let plane = projective_plane().complex;
+        for x in [&circle, &points, &plane, &wedge(2).complex] {
+            let chi = x.euler();
+            for y in [&circle, &points, &plane] {
+                let psi = y.euler();
+                assert_eq!(x.product(y).euler(), chi * psi);
+                assert_eq!(x.disjoint_union(y).euler(), chi + psi);
+                assert_eq!(x.join(y).euler(), chi + psi - chi * psi);
+            }
+
+            assert_eq!(x.cone().euler(), 1);
+            assert_eq!(x.suspension().euler(), 2 - chi);
+            assert_eq!(x.product(&circle).validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_operations_counts() {
+        let circle = sphere(1).complex;
+
+        // The cone over a circle is a disc; the suspension a sphere.
+        let disc = circle.cone();
+        assert_eq!(disc.count_dim(0), 4);
+        assert_eq!(disc.count_dim(2), 3);
+        assert_eq!(circle.suspension().count_dim(2), 6);
+
+        // The torus as a product of two triangles; nine squares, each split
+        // in two.
+        let torus = circle.product(&circle);
+        assert_eq!(torus.count_dim(0), 9);
+        assert_eq!(torus.count_dim(1), 27);
+        assert_eq!(torus.count_dim(2), 18);
+
+        // Two points joined with two points make a square.
+        let points = sphere(0).complex;
+        let square = points.join(&points);
+        assert_eq!((square.count_dim(0), square.count_dim(1)), (4, 4));
+        assert_eq!(points.disjoint_union(&circle).count_dim(0), 5);
+    }
+
+    #[test]
+    fn test_operations_filtration() {
+        // An edge entering at 1.0 and a vertex at 2.0.
+        let edge: SimplicialComplex = ComplexBuilder::new()
+            .simplex(&[4, 9], Some(1.0))
+            .unwrap()
+            .build();
+        let point: SimplicialComplex = ComplexBuilder::new()
+            .simplex(&[7], Some(2.0))
+            .unwrap()
+            .build();
+
+        // The vertices are relabelled; the triangle enters with the point.
+        let triangle = edge.join(&point);
+        assert_eq!(triangle.validate(), Ok(()));
+        assert_eq!(triangle.locate(&[0, 1]).unwrap().weight(), 1.0);
+        assert_eq!(triangle.locate(&[0, 1, 2]).unwrap().weight(), 2.0);
+
+        let prism = edge.product(&point);
+        assert_eq!(prism.count(), 3);
+        assert!(prism.into_iter().all(|s| s.weight() == 2.0));
+
+        let cone = edge.cone();
+        assert_eq!(cone.locate(&[2]).unwrap().weight(), 0.0);
+        assert_eq!(cone.locate(&[0, 2]).unwrap().weight(), 1.0);
+    }
+}
@@ -4,12 +4,13 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::slice;
 
-/// A Vertex consists of an id and a weight.
+/// A Vertex consists of an id and a weight; the weight is the filtration
+/// value of the simplex the vertex completes.
 #[derive(Debug, PartialEq, Copy, Clone, Default)]
-pub struct Vertex(usize, usize);
+pub struct Vertex(usize, f64);
 
 impl Vertex {
-    pub fn new(id: usize, weight: usize) -> Self {
+    pub fn new(id: usize, weight: f64) -> Self {
         Vertex(id, weight)
     }
 
@@ -17,7 +18,7 @@ impl Vertex {
         self.0
     }
 
-    pub fn weight(&self) -> usize {
+    pub fn weight(&self) -> f64 {
         self.1
     }
 }
@@ -25,7 +26,7 @@ impl Vertex {
 impl Ord for Vertex {
     /// Compare two vertices; first by weight then id.
     fn cmp(&self, other: &Self) -> Ordering {
-        let r = self.1.cmp(&other.1);
+        let r = self.1.total_cmp(&other.1);
         match r {
             Ordering::Equal => self.0.cmp(&other.0),
             _ => r,
@@ -57,7 +58,7 @@ impl Simplex {
     }
 
     /// Return the weight of a Simplex, which is the last added edge.
-    pub fn weight(&self) -> usize {
+    pub fn weight(&self) -> f64 {
         match self.0.iter().reduce(|a, b| if a.1 > b.1 { a } else { b }) {
             Some(vertex) => vertex.1,
             None => panic!("Encountered a Simplex with no vertices."),
@@ -80,16 +81,17 @@ impl Simplex {
     /// Return whether this simplex is a face of another simplex.
     pub fn is_face(&self, other: &Self) -> bool {
         // Avoid underflow.
-        match other.dim() {
-            0 => return false,
-            _ => (),
+        if other.dim() == 0 {
+            return false;
         }
 
         match self.dim() == other.dim() - 1 {
             true => {
-                // Each vertex should be a vertex on the other simplex.
+                // Each vertex should be a vertex on the other simplex; the
+                // weights may differ, as a face can enter the filtration
+                // before its coface.
                 for vrtx in self {
-                    if !other.0.contains(vrtx) {
+                    if !other.0.iter().any(|v| v.0 == vrtx.0) {
                         return false;
                     }
                 }
@@ -102,7 +104,7 @@ impl Simplex {
 
 impl Ord for Simplex {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.weight().cmp(&other.weight())
+        self.weight().total_cmp(&other.weight())
     }
 }
 
@@ -149,45 +151,45 @@ mod tests {
 
     #[test]
     fn test_vertex_fmt() {
-        let vertex = Vertex(1, 2);
+        let vertex = Vertex(1, 2.0);
         assert_eq!(format!("{}", vertex), "(1, 2)");
     }
 
     #[test]
     fn test_simplex_weight() {
-        let simplex = Simplex(vec![Vertex(1, 5), Vertex(2, 3), Vertex(5, 1)]);
-        assert_eq!(simplex.weight(), 5);
+        let simplex = Simplex(vec![Vertex(1, 5.0), Vertex(2, 3.0), Vertex(5, 1.0)]);
+        assert_eq!(simplex.weight(), 5.0);
     }
 
     #[test]
     fn test_simplex_dimension() {
-        let simplex = Simplex(vec![Vertex(1, 5), Vertex(2, 3), Vertex(5, 1)]);
+        let simplex = Simplex(vec![Vertex(1, 5.0), Vertex(2, 3.0), Vertex(5, 1.0)]);
         assert_eq!(simplex.dim(), 2);
     }
 
     #[test]
     fn test_simplex_iterator() {
-        let simplex = Simplex(vec![Vertex(1, 0), Vertex(2, 3), Vertex(5, 1)]);
+        let simplex = Simplex(vec![Vertex(1, 0.0), Vertex(2, 3.0), Vertex(5, 1.0)]);
         let mut iter = simplex.into_iter();
-        assert_eq!(iter.next(), Some(Vertex(1, 0)));
-        assert_eq!(iter.next(), Some(Vertex(2, 3)));
-        assert_eq!(iter.next(), Some(Vertex(5, 1)));
+        assert_eq!(iter.next(), Some(Vertex(1, 0.0)));
+        assert_eq!(iter.next(), Some(Vertex(2, 3.0)));
+        assert_eq!(iter.next(), Some(Vertex(5, 1.0)));
         assert_eq!(iter.next(), None);
     }
 
     #[test]
     fn test_simplex_ref_iterator() {
-        let simplex = &Simplex(vec![Vertex(1, 0), Vertex(2, 3), Vertex(5, 1)]);
+        let simplex = &Simplex(vec![Vertex(1, 0.0), Vertex(2, 3.0), Vertex(5, 1.0)]);
         let mut iter = simplex.into_iter();
-        assert_eq!(iter.next(), Some(&Vertex(1, 0)));
-        assert_eq!(iter.next(), Some(&Vertex(2, 3)));
-        assert_eq!(iter.next(), Some(&Vertex(5, 1)));
+        assert_eq!(iter.next(), Some(&Vertex(1, 0.0)));
+        assert_eq!(iter.next(), Some(&Vertex(2, 3.0)));
+        assert_eq!(iter.next(), Some(&Vertex(5, 1.0)));
         assert_eq!(iter.next(), None);
     }
 
     #[test]
     fn test_simplex_fmt() {
-        let simplex = Simplex(vec![Vertex(1, 0), Vertex(2, 1), Vertex(5, 2)]);
+        let simplex = Simplex(vec![Vertex(1, 0.0), Vertex(2, 1.0), Vertex(5, 2.0)]);
         assert_eq!(
             format!("{}", simplex),
             "Simplex((1, 0), (2, 1), (5, 2), weight: 2)"
@@ -197,18 +199,18 @@ mod tests {
     #[test]
     fn test_simplex_is_face() {
         // 0-skeleton case.
-        let simplex_a = Simplex(vec![Vertex(1, 0)]);
-        let simplex_b = Simplex(vec![Vertex(2, 0)]);
+        let simplex_a = Simplex(vec![Vertex(1, 0.0)]);
+        let simplex_b = Simplex(vec![Vertex(2, 0.0)]);
         assert_eq!(simplex_a.is_face(&simplex_b), false);
 
         // 1-skeleton false case.
-        let simplex_a = Simplex(vec![Vertex(1, 0)]);
-        let simplex_b = Simplex(vec![Vertex(2, 0), Vertex(3, 0)]);
+        let simplex_a = Simplex(vec![Vertex(1, 0.0)]);
+        let simplex_b = Simplex(vec![Vertex(2, 0.0), Vertex(3, 0.0)]);
         assert_eq!(simplex_a.is_face(&simplex_b), false);
 
         // 1-skeleton true case.
-        let simplex_a = Simplex(vec![Vertex(1, 0)]);
-        let simplex_b = Simplex(vec![Vertex(1, 0), Vertex(3, 0)]);
+        let simplex_a = Simplex(vec![Vertex(1, 0.0)]);
+        let simplex_b = Simplex(vec![Vertex(1, 0.0), Vertex(3, 0.0)]);
         assert_eq!(simplex_a.is_face(&simplex_b), true);
     }
 }
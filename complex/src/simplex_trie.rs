@@ -2,7 +2,8 @@
 //! Referenced from: 'The Simplex Tree: An Efficient Data Structure for
 //! General Simplicial Complexes - Jean-Daniel Boissonnat · Clément Maria'
 use crate::simplex::{Simplex, Vertex};
-use std::collections::{BTreeMap, VecDeque};
+use crate::storage::{facets, ids, link};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 // We're attempting to produce a trie structure; as an example:
 //   1     2   3   4
@@ -10,31 +11,50 @@ use std::collections::{BTreeMap, VecDeque};
 // 3
 // Contains the simplices: (1, 2, 3), (1, 2), (1, 3), (1), (2), ... etc.
 
-struct Node<T>(BTreeMap<T, Node<T>>);
+/// A node of the trie; its children keyed by the id of their vertex, and
+/// the weight of its own vertex. Keying on the id alone means a simplex added
+/// again with other weights lands on the same path, updating it, rather than
+/// branching off beside it.
+struct Node(BTreeMap<usize, Node>, f64);
 
-impl<T> Node<T>
-where
-    T: Ord + Copy,
-{
+impl Node {
     pub fn new() -> Self {
-        Node(BTreeMap::new())
+        Node(BTreeMap::new(), 0.0)
     }
 
-    /// Add a value to the trie.
-    pub fn add(&mut self, value: &[T]) {
-        if let Some((x, xs)) = value.split_first() {
-            if !self.0.contains_key(x) {
-                self.0.insert(*x, Node::new());
+    /// Add a value to the trie, returning the number of nodes created; the
+    /// longest prefixes of the value, as each node is created beneath the
+    /// last. Each node along the path takes the weight of its vertex.
+    pub fn add(&mut self, value: &[Vertex]) -> usize {
+        match value.split_first() {
+            Some((x, xs)) => {
+                let created = !self.0.contains_key(&x.id());
+                let node = self.0.entry(x.id()).or_insert_with(Node::new);
+                node.1 = x.weight();
+                node.add(xs) + created as usize
             }
+            None => 0,
+        }
+    }
 
-            self.0.get_mut(x).unwrap().add(xs);
+    /// Merge another trie into this one; the union of both. Nodes within
+    /// both keep the weights of this trie.
+    pub fn merge(&mut self, other: Node) {
+        for (key, child) in other.0 {
+            match self.0.get_mut(&key) {
+                Some(node) => node.merge(child),
+                None => {
+                    self.0.insert(key, child);
+                }
+            }
         }
     }
 
-    /// Determine whether a value is contained within the trie.
-    pub fn contains(&self, value: &[T]) -> bool {
+    /// Determine whether the ids of a value are contained within the trie,
+    /// whatever their weights.
+    pub fn contains(&self, value: &[Vertex]) -> bool {
         if let Some((x, xs)) = value.split_first() {
-            match self.0.get(x) {
+            match self.0.get(&x.id()) {
                 Some(v) => v.contains(xs),
                 None => false,
             }
@@ -42,11 +62,52 @@ where
             true
         }
     }
+
+    /// Find the path below this node through exactly the vertices 'ids', in
+    /// whichever order they were added, pushing it onto 'path'.
+    fn locate(&self, ids: &[usize], path: &mut Vec<Vertex>) -> bool {
+        if ids.is_empty() {
+            return true;
+        }
+
+        for id in ids {
+            if let Some(child) = self.0.get(id) {
+                let rest: Vec<usize> = ids.iter().copied().filter(|i| i != id).collect();
+                path.push(Vertex::new(*id, child.1));
+                if child.locate(&rest, path) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+
+        false
+    }
+
+    /// Remove every path below this node containing each of 'ids', along
+    /// with everything beneath it.
+    fn remove(&mut self, ids: &[usize]) {
+        self.0.retain(|id, child| {
+            let rest: Vec<usize> = ids.iter().copied().filter(|i| i != id).collect();
+            if rest.is_empty() {
+                return false;
+            }
+
+            child.remove(&rest);
+            true
+        });
+    }
 }
 
 /// A SimplexTrie contains a single root node, corresponding to the empty
 /// set; simplices contained within K begin as child nodes off that root node.
-pub struct SimplexTrie(Node<Vertex>);
+/// Alongside, each simplex is given an index within its dimension as it is
+/// inserted, keyed by its ids in the order of its path; indices are stable
+/// as others are inserted, and only change, keeping their order, when
+/// simplices are removed. Keying on the path, rather than the ordered ids,
+/// keeps the counts in step with iteration should the same vertices be
+/// added in two orders.
+pub struct SimplexTrie(Node, Vec<HashMap<Vec<usize>, usize>>);
 
 impl Default for SimplexTrie {
     fn default() -> Self {
@@ -57,40 +118,157 @@ impl Default for SimplexTrie {
 impl SimplexTrie {
     /// Create a new, empty SimplexTrie
     pub fn new() -> Self {
-        SimplexTrie(Node::new())
+        SimplexTrie(Node::new(), Vec::new())
     }
 
     /// Create a new SimplexTrie with a 0-skeleton of size 'sz'.
     pub fn new_skel(sz: usize) -> Self {
-        let mut st = SimplexTrie(Node::new());
+        let mut st = SimplexTrie::new();
         for i in 0..sz {
-            st.0.add(&[Vertex::new(i, 0); 1])
+            st.add_simplex(&Simplex::new(vec![Vertex::new(i, 0.0)]));
         }
 
         st
     }
 
+    /// Give the simplex along the path 'ids' the next index within its
+    /// dimension, unless it already holds one.
+    fn insert_index(&mut self, ids: Vec<usize>) {
+        if self.1.len() < ids.len() {
+            self.1.resize_with(ids.len(), HashMap::new);
+        }
+
+        let dim = &mut self.1[ids.len() - 1];
+        let next = dim.len();
+        dim.entry(ids).or_insert(next);
+    }
+
     // TODO: Rather than exposing a public function '.vertices()' on Simplex,
     // is there a trait for 'to_slice()' ? Or utilize the fact we've already
     // implemented iterators for Simplex.
     pub fn add_simplex(&mut self, simplex: &Simplex) {
-        self.0.add(simplex.vertices())
+        let vertices = simplex.vertices();
+        let created = self.0.add(vertices);
+        for len in vertices.len() + 1 - created..=vertices.len() {
+            let ids: Vec<usize> = vertices[..len].iter().map(|v| v.id()).collect();
+            self.insert_index(ids);
+        }
     }
 
     // TODO: See above note in 'add_simplex'.
+    /// Return whether the trie holds a simplex on the vertices of 'simplex',
+    /// in the same order; weights aren't compared.
     pub fn contains_simplex(&self, simplex: &Simplex) -> bool {
         self.0.contains(simplex.vertices())
     }
 
+    /// Return the number of simplices.
+    pub fn count(&self) -> usize {
+        self.1.iter().map(|dim| dim.len()).sum()
+    }
+
+    /// Return the number of simplices of dimension 'sz'.
+    pub fn count_dim(&self, sz: usize) -> usize {
+        self.1.get(sz).map_or(0, |dim| dim.len())
+    }
+
+    /// Return the index, within its dimension, of the simplex with exactly
+    /// the vertices 'ids'.
+    pub fn index(&self, ids: &[usize]) -> Option<usize> {
+        let path: Vec<usize> = self
+            .locate(ids)?
+            .vertices()
+            .iter()
+            .map(|v| v.id())
+            .collect();
+        self.1.get(path.len() - 1)?.get(&path).copied()
+    }
+
+    /// Merge the simplices of another SimplexTrie into this one; those new
+    /// to this trie are indexed after its own, in their former order.
+    pub fn merge(&mut self, other: SimplexTrie) {
+        for dim in other.1 {
+            let mut simplices: Vec<(Vec<usize>, usize)> = dim.into_iter().collect();
+            simplices.sort_unstable_by_key(|(_, i)| *i);
+            for (ids, _) in simplices {
+                self.insert_index(ids);
+            }
+        }
+
+        self.0.merge(other.0)
+    }
+
+    /// Return the simplex with exactly the vertices 'ids', as it was added.
+    pub fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        let mut path = vec![];
+        match self.0.locate(ids, &mut path) {
+            true if !path.is_empty() => Some(Simplex::new(path)),
+            _ => None,
+        }
+    }
+
+    /// Remove a simplex along with its cofaces, leaving a valid complex.
+    pub fn remove_simplex(&mut self, simplex: &Simplex) {
+        let ids = ids(simplex);
+        self.0.remove(&ids);
+
+        // Those left keep their order, closing the gaps.
+        for dim in &mut self.1 {
+            dim.retain(|k, _| !ids.iter().all(|i| k.contains(i)));
+            let mut indices: Vec<&mut usize> = dim.values_mut().collect();
+            indices.sort_unstable_by_key(|i| **i);
+            for (n, i) in indices.into_iter().enumerate() {
+                *i = n;
+            }
+        }
+    }
+
+    /// Return the facets of a simplex, its faces of one dimension lower.
+    pub fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        facets(simplex, |ids| self.locate(ids))
+    }
+
+    /// Return the proper cofaces of a simplex, every simplex containing it;
+    /// only the ids of higher dimensions within the index are visited, each
+    /// dimension in the order of its indices.
+    pub fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let ids = ids(simplex);
+
+        let mut r = vec![];
+        for dim in self.1.iter().skip(ids.len()) {
+            let mut found: Vec<(&Vec<usize>, &usize)> = dim
+                .iter()
+                .filter(|(k, _)| ids.iter().all(|i| k.contains(i)))
+                .collect();
+            found.sort_unstable_by_key(|(_, i)| **i);
+            r.extend(found.into_iter().filter_map(|(k, _)| self.locate(k)));
+        }
+
+        r
+    }
+
+    /// Return the star of a simplex; the simplex and its cofaces.
+    pub fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let mut r: Vec<Simplex> = self.locate(&ids(simplex)).into_iter().collect();
+        r.extend(self.cofaces(simplex));
+        r
+    }
+
+    /// Return the link of a simplex; every simplex disjoint from it whose
+    /// union with it is a simplex.
+    pub fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        link(simplex, &self.cofaces(simplex), |ids| self.locate(ids))
+    }
+
     /// Iterate, returning simplices of dimension 'sz'.
-    pub fn iter_dim(&self, sz: usize) -> SimplexTrieIterator {
+    pub fn iter_dim(&self, sz: usize) -> SimplexTrieIterator<'_> {
         let mut iter = self.into_iter();
         iter.1 = Some(sz);
         iter
     }
 }
 
-type QueueValue<'a> = ((&'a Vertex, &'a Node<Vertex>), bool);
+type QueueValue<'a> = ((&'a usize, &'a Node), bool);
 
 /// Queue, Option<Dimension>.
 pub struct SimplexTrieIterator<'a>(VecDeque<QueueValue<'a>>, Option<usize>);
@@ -99,49 +277,43 @@ impl<'a> Iterator for SimplexTrieIterator<'a> {
     type Item = Simplex;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.0.pop_front() {
-            None => None,
-            Some(x) => {
-                let mut head = vec![x];
-
-                // Pop values off until we find another head value or exhaust
-                // the queue.
-                while let Some(y) = self.0.front() {
-                    match y.1 {
-                        true => break,
-                        false => {
-                            // Encountered a child value, pop off the queue
-                            // and push to the head.
-                            head.push(self.0.pop_front().unwrap());
-                        }
+        // Simplices of other dimensions are skipped over.
+        loop {
+            let x = self.0.pop_front()?;
+            let mut head = vec![x];
+
+            // Pop values off until we find another head value or exhaust
+            // the queue.
+            while let Some(y) = self.0.front() {
+                match y.1 {
+                    true => break,
+                    false => {
+                        // Encountered a child value, pop off the queue
+                        // and push to the head.
+                        head.push(self.0.pop_front().unwrap());
                     }
                 }
+            }
 
-                let smplx = Simplex::new(head.iter().map(|a| *a.0 .0).collect());
-
-                // If the last value of head has children, we need to push
-                // them onto the stack if:
-                // A) there is no dimensional constraint on the iterator.
-                //  OR
-                // B) the dimension of the above simplex 'smplx' is smaller
-                // than the dimensional constraint.
-
-                // TODO: Could be written in such a way that the for loop
-                // is written only once; will the compiler hoist out the
-                // dimension check?
-                match self.1 {
-                    Some(dim) => match dim > smplx.dim() {
-                        true => {
-                            for child in head.last().unwrap().0 .1 .0.iter() {
-                                let mut smplx = head.clone();
-                                smplx.push((child, false));
-                                self.0.extend(smplx);
-                            }
-                        }
-                        false => (),
-                    },
-
-                    None => {
+            let smplx = Simplex::new(
+                head.iter()
+                    .map(|a| Vertex::new(*a.0 .0, a.0 .1 .1))
+                    .collect(),
+            );
+
+            // If the last value of head has children, we need to push
+            // them onto the stack if:
+            // A) there is no dimensional constraint on the iterator.
+            //  OR
+            // B) the dimension of the above simplex 'smplx' is smaller
+            // than the dimensional constraint.
+
+            // TODO: Could be written in such a way that the for loop
+            // is written only once; will the compiler hoist out the
+            // dimension check?
+            match self.1 {
+                Some(dim) => {
+                    if dim > smplx.dim() {
                         for child in head.last().unwrap().0 .1 .0.iter() {
                             let mut smplx = head.clone();
                             smplx.push((child, false));
@@ -150,15 +322,20 @@ impl<'a> Iterator for SimplexTrieIterator<'a> {
                     }
                 }
 
-                // Return the smplx if it matches our dimensional constraint.
-                match self.1 {
-                    Some(dim) => match dim == smplx.dim() {
-                        true => Some(smplx),
-                        false => self.next(),
-                    },
-                    None => Some(smplx),
+                None => {
+                    for child in head.last().unwrap().0 .1 .0.iter() {
+                        let mut smplx = head.clone();
+                        smplx.push((child, false));
+                        self.0.extend(smplx);
+                    }
                 }
             }
+
+            // Return the smplx if it matches our dimensional constraint.
+            match self.1 {
+                Some(dim) if dim != smplx.dim() => continue,
+                _ => return Some(smplx),
+            }
         }
     }
 }
@@ -185,25 +362,25 @@ mod tests {
 
     #[test]
     fn test_node_add_contains() {
-        let mut root = Node::<Vertex>::new();
+        let mut root = Node::new();
 
         let simplex = Simplex::new(vec![
-            Vertex::new(1, 0),
-            Vertex::new(2, 0),
-            Vertex::new(3, 4),
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 0.0),
+            Vertex::new(3, 4.0),
         ]);
         root.add(simplex.vertices());
         assert_eq!(root.contains(simplex.vertices()), true);
 
         // Should return false.
-        let simplex = Simplex::new(vec![Vertex::new(2, 0)]);
+        let simplex = Simplex::new(vec![Vertex::new(2, 0.0)]);
         assert_eq!(root.contains(simplex.vertices()), false);
     }
 
     #[test]
     fn test_simplex_trie_iter() {
         let mut st = SimplexTrie::new_skel(10);
-        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0), Vertex::new(2, 3)]);
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
         st.add_simplex(&smplx_1);
 
         // 11-simplices (10 0-skel, 1 1-skel).
@@ -224,7 +401,7 @@ mod tests {
         for i in 0..10 {
             let value = st_iter.next();
             assert_eq!(value.is_some(), true);
-            assert_eq!(value.unwrap(), Simplex::new(vec![Vertex::new(i, 0)]));
+            assert_eq!(value.unwrap(), Simplex::new(vec![Vertex::new(i, 0.0)]));
         }
 
         // No more simplices.
@@ -235,12 +412,12 @@ mod tests {
     fn test_simplex_trie_iter_dim_1_skel() {
         let mut st = SimplexTrie::new_skel(10);
 
-        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0), Vertex::new(2, 3)]);
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
         st.add_simplex(&smplx_1);
         let smplx_2 = Simplex::new(vec![
-            Vertex::new(1, 0),
-            Vertex::new(2, 3),
-            Vertex::new(3, 4),
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 3.0),
+            Vertex::new(3, 4.0),
         ]);
         st.add_simplex(&smplx_2);
 
@@ -254,4 +431,188 @@ mod tests {
         // No more simplices should be returned.
         assert_eq!(st_iter.next().is_none(), true);
     }
+
+    #[test]
+    fn test_simplex_trie_iter_dim_large() {
+        // Every vertex is passed over before the single edge is reached.
+        let n = 200_000;
+        let mut st = SimplexTrie::new_skel(n);
+        st.add_simplex(&Simplex::new(vec![
+            Vertex::new(n - 2, 0.0),
+            Vertex::new(n - 1, 0.0),
+        ]));
+
+        assert_eq!(st.iter_dim(1).count(), 1);
+    }
+
+    #[test]
+    fn test_simplex_trie_merge() {
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
+        let smplx_2 = Simplex::new(vec![
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 3.0),
+            Vertex::new(3, 4.0),
+        ]);
+        let smplx_3 = Simplex::new(vec![Vertex::new(4, 0.0), Vertex::new(0, 1.0)]);
+
+        let mut a = SimplexTrie::new_skel(5);
+        a.add_simplex(&smplx_1);
+        let mut b = SimplexTrie::new_skel(5);
+        b.add_simplex(&smplx_2);
+        b.add_simplex(&smplx_3);
+
+        a.merge(b);
+        for smplx in [&smplx_1, &smplx_2, &smplx_3] {
+            assert!(a.contains_simplex(smplx));
+        }
+
+        // 5 vertices, 2 edges and a triangle.
+        assert_eq!(a.into_iter().count(), 8);
+        assert_eq!(a.count(), 8);
+
+        // Those new to 'a' follow its own.
+        assert_eq!(a.index(&[1, 2]), Some(0));
+        assert_eq!(a.index(&[0, 4]), Some(1));
+    }
+
+    /// A triangle (0, 1, 2) with an edge (2, 3) and a lone vertex 4, each
+    /// added with its vertices ordered by decreasing id.
+    fn local() -> SimplexTrie {
+        let mut st = SimplexTrie::new_skel(5);
+        let v = Vertex::new;
+        for smplx in [
+            vec![v(1, 0.0), v(0, 1.0)],
+            vec![v(2, 0.0), v(0, 2.0)],
+            vec![v(2, 0.0), v(1, 1.5)],
+            vec![v(3, 0.0), v(2, 0.5)],
+            vec![v(2, 0.0), v(1, 1.5), v(0, 2.0)],
+        ] {
+            st.add_simplex(&Simplex::new(smplx));
+        }
+
+        st
+    }
+
+    #[test]
+    fn test_simplex_trie_locate() {
+        let st = local();
+        let edge = Simplex::new(vec![Vertex::new(2, 0.0), Vertex::new(0, 2.0)]);
+        assert_eq!(st.locate(&[0, 2]), Some(edge.clone()));
+        assert_eq!(st.locate(&[2, 0]), Some(edge));
+        assert_eq!(st.locate(&[0, 3]), None);
+        assert_eq!(st.locate(&[]), None);
+    }
+
+    #[test]
+    fn test_simplex_trie_facets_cofaces() {
+        let st = local();
+        let triangle = st.locate(&[0, 1, 2]).unwrap();
+        let facets = st.facets(&triangle);
+        assert_eq!(facets.len(), 3);
+        assert!(facets.iter().all(|f| f.is_face(&triangle)));
+        assert!(st.facets(&st.locate(&[4]).unwrap()).is_empty());
+
+        // Three edges and the triangle contain vertex 2.
+        let vertex = st.locate(&[2]).unwrap();
+        let cofaces = st.cofaces(&vertex);
+        assert_eq!(cofaces.len(), 4);
+        assert!(cofaces.contains(&triangle));
+        assert_eq!(st.star(&vertex).len(), 5);
+        assert_eq!(st.star(&vertex)[0], vertex);
+        assert!(st.cofaces(&triangle).is_empty());
+    }
+
+    #[test]
+    fn test_simplex_trie_remove() {
+        let mut st = local();
+
+        // Removing vertex 2 removes each simplex containing it.
+        st.remove_simplex(&st.locate(&[2]).unwrap());
+        assert_eq!(st.into_iter().count(), 5);
+        assert!(st.locate(&[0, 1]).is_some());
+        assert!(st.locate(&[2, 3]).is_none());
+
+        let mut st = local();
+        st.remove_simplex(&st.locate(&[0, 1]).unwrap());
+        assert!(st.locate(&[0, 1, 2]).is_none());
+        assert!(st.locate(&[0, 2]).is_some());
+        assert_eq!(st.into_iter().count(), 8);
+    }
+
+    #[test]
+    fn test_simplex_trie_index() {
+        let mut st = local();
+        assert_eq!(st.count_dim(0), 5);
+        assert_eq!(st.count_dim(1), 4);
+        assert_eq!(st.count_dim(2), 1);
+        assert_eq!(st.count_dim(3), 0);
+
+        // Indexed as inserted, whatever the order of the ids.
+        assert_eq!(st.index(&[4]), Some(4));
+        assert_eq!(st.index(&[0, 1]), Some(0));
+        assert_eq!(st.index(&[3, 2]), Some(3));
+        assert_eq!(st.index(&[2, 1, 0]), Some(0));
+        assert_eq!(st.index(&[0, 3]), None);
+        assert_eq!(st.index(&[]), None);
+
+        // Adding a simplex again changes nothing.
+        st.add_simplex(&Simplex::new(vec![
+            Vertex::new(2, 0.0),
+            Vertex::new(0, 2.0),
+        ]));
+        assert_eq!(st.count_dim(1), 4);
+        assert_eq!(st.index(&[0, 2]), Some(1));
+
+        // Nor does adding it with other weights, bar the weights.
+        st.add_simplex(&Simplex::new(vec![
+            Vertex::new(2, 0.0),
+            Vertex::new(0, 1.75),
+        ]));
+        assert_eq!(st.count_dim(1), 4);
+        assert_eq!(st.index(&[0, 2]), Some(1));
+        assert_eq!(st.locate(&[0, 2]).unwrap().weight(), 1.75);
+
+        // Those left keep their order.
+        st.remove_simplex(&st.locate(&[0, 2]).unwrap());
+        assert_eq!(st.count_dim(1), 3);
+        assert_eq!(st.count_dim(2), 0);
+        assert_eq!(st.index(&[0, 1]), Some(0));
+        assert_eq!(st.index(&[1, 2]), Some(1));
+        assert_eq!(st.index(&[2, 3]), Some(2));
+        assert_eq!(st.count(), st.into_iter().count());
+    }
+
+    #[test]
+    fn test_simplex_trie_index_orders() {
+        // The same vertices added in two orders lie along two paths, each
+        // counted and indexed.
+        let mut st = SimplexTrie::new();
+        let v = Vertex::new;
+        st.add_simplex(&Simplex::new(vec![v(1, 0.0), v(2, 0.0)]));
+        st.add_simplex(&Simplex::new(vec![v(2, 0.0), v(1, 0.0)]));
+
+        assert_eq!(st.count_dim(1), 2);
+        assert_eq!(st.iter_dim(1).count(), 2);
+        assert_eq!(st.count(), st.into_iter().count());
+        assert!(st.index(&[1, 2]).is_some());
+        assert_eq!(st.cofaces(&Simplex::new(vec![v(1, 0.0)])).len(), 2);
+    }
+
+    #[test]
+    fn test_simplex_trie_link() {
+        let st = local();
+
+        // The vertices 0, 1 and 3 and the edge (0, 1).
+        let link = st.link(&st.locate(&[2]).unwrap());
+        assert_eq!(link.len(), 4);
+        assert!(link.contains(&st.locate(&[0, 1]).unwrap()));
+        assert!(link.contains(&st.locate(&[3]).unwrap()));
+
+        assert_eq!(
+            st.link(&st.locate(&[1, 2]).unwrap()),
+            vec![st.locate(&[0]).unwrap()]
+        );
+        assert!(st.link(&st.locate(&[2, 3]).unwrap()).is_empty());
+        assert!(st.link(&st.locate(&[4]).unwrap()).is_empty());
+    }
 }
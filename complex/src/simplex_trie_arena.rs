@@ -2,6 +2,7 @@
 //! Referenced from: 'The Simplex Tree: An Efficient Data Structure for
 //! General Simplicial Complexes - Jean-Daniel Boissonnat · Clément Maria'
 use crate::simplex::{Simplex, Vertex};
+use crate::storage::{facets, ids, link};
 
 /// A trie backed with an arena.
 struct TrieArena<T> {
@@ -39,14 +40,17 @@ where
 
         t
     }
+}
 
-    fn _add(&mut self, value: &[T], parent: usize, depth: usize) {
+impl TrieArena<Vertex> {
+    fn _add(&mut self, value: &[Vertex], parent: usize, depth: usize) {
         if let Some((x, xs)) = value.split_first() {
-            // Attempt to find the value x within the child values of
-            // our parent.
+            // Attempt to find the id of x within the child values of our
+            // parent; the node found takes the weight of x.
             for child in self.children.get(parent).unwrap() {
-                if self.arena.get(*child).unwrap() == x {
+                if self.arena.get(*child).unwrap().id() == x.id() {
                     let new_parent = *child;
+                    self.arena[new_parent] = *x;
                     return self._add(xs, new_parent, depth + 1);
                 }
             }
@@ -68,19 +72,20 @@ where
             // x resides in the current depth.
             self.depth.get_mut(depth).unwrap().push(x_index);
 
-            return self._add(xs, x_index, depth + 1);
+            self._add(xs, x_index, depth + 1)
         }
     }
 
-    /// Add a value to the trie.
-    pub fn add(&mut self, value: &[T]) {
+    /// Add a value to the trie; nodes are matched by the id of their vertex
+    /// alone, so a value added again with other weights updates its path.
+    pub fn add(&mut self, value: &[Vertex]) {
         self._add(value, 0, 0)
     }
 
-    fn _contains(&self, value: &[T], parent: usize) -> bool {
+    fn _contains(&self, value: &[Vertex], parent: usize) -> bool {
         if let Some((x, xs)) = value.split_first() {
             for child in self.children.get(parent).unwrap() {
-                if self.arena.get(*child).unwrap() == x {
+                if self.arena.get(*child).unwrap().id() == x.id() {
                     let new_parent = *child;
                     return self._contains(xs, new_parent);
                 }
@@ -91,9 +96,38 @@ where
         }
     }
 
-    pub fn contains(&self, value: &[T]) -> bool {
+    pub fn contains(&self, value: &[Vertex]) -> bool {
         self._contains(value, 0)
     }
+
+    /// Return the values on the path from the root to 'index'.
+    fn path(&self, mut index: usize) -> Vec<Vertex> {
+        let mut r = vec![];
+        while index != 0 {
+            r.push(self.arena[index]);
+            index = self.parent[index];
+        }
+
+        r.reverse();
+        r
+    }
+
+    /// Find the node below 'parent' whose path through it holds exactly the
+    /// vertices 'ids', in whichever order they were added.
+    fn locate(&self, ids: &[usize], parent: usize) -> Option<usize> {
+        if ids.is_empty() {
+            return Some(parent);
+        }
+
+        self.children[parent]
+            .iter()
+            .filter(|c| ids.contains(&self.arena[**c].id()))
+            .find_map(|c| {
+                let id = self.arena[*c].id();
+                let rest: Vec<usize> = ids.iter().copied().filter(|i| *i != id).collect();
+                self.locate(&rest, *c)
+            })
+    }
 }
 
 pub struct SimplexTrie(TrieArena<Vertex>);
@@ -111,10 +145,10 @@ impl SimplexTrie {
     }
 
     /// Create a new SimplexTrie with a 0-skeleton of size 'sz'.
-    fn new_skel(sz: usize) -> Self {
+    pub fn new_skel(sz: usize) -> Self {
         let mut st = SimplexTrie::new();
         for i in 0..sz {
-            st.0.add(&[Vertex::new(i, 0); 1]);
+            st.0.add(&[Vertex::new(i, 0.0); 1]);
         }
 
         st
@@ -128,7 +162,58 @@ impl SimplexTrie {
         self.0.contains(simplex.vertices())
     }
 
-    pub fn iter_dim(&self, sz: usize) -> SimplexTrieIterator {
+    /// Return the simplex with exactly the vertices 'ids', as it was added.
+    pub fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        match self.0.locate(ids, 0) {
+            Some(index) if index != 0 => Some(Simplex::new(self.0.path(index))),
+            _ => None,
+        }
+    }
+
+    /// Return the facets of a simplex, its faces of one dimension lower.
+    pub fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        facets(simplex, |ids| self.locate(ids))
+    }
+
+    /// Return the proper cofaces of a simplex; only the nodes deeper than
+    /// the simplex, found with the depth lists, are visited.
+    pub fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let ids = ids(simplex);
+        self.0
+            .depth
+            .iter()
+            .skip(ids.len())
+            .flatten()
+            .map(|index| self.0.path(*index))
+            .filter(|path| ids.iter().all(|i| path.iter().any(|v| v.id() == *i)))
+            .map(Simplex::new)
+            .collect()
+    }
+
+    /// Return the star of a simplex; the simplex and its cofaces.
+    pub fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let mut r: Vec<Simplex> = self.locate(&ids(simplex)).into_iter().collect();
+        r.extend(self.cofaces(simplex));
+        r
+    }
+
+    /// Return the link of a simplex; every simplex disjoint from it whose
+    /// union with it is a simplex.
+    pub fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        link(simplex, &self.cofaces(simplex), |ids| self.locate(ids))
+    }
+
+    /// Return the number of simplices; every node but the root.
+    pub fn count(&self) -> usize {
+        self.0.arena.len() - 1
+    }
+
+    /// Return the number of simplices of dimension 'sz'.
+    pub fn count_dim(&self, sz: usize) -> usize {
+        self.0.depth.get(sz).map_or(0, |depth| depth.len())
+    }
+
+    pub fn iter_dim(&self, sz: usize) -> SimplexTrieIterator<'_> {
         let mut iter = self.into_iter();
         iter.0 = sz;
         iter.3 = true;
@@ -148,28 +233,17 @@ impl<'a> Iterator for SimplexTrieIterator<'a> {
             // If we can fetch something at 'index', then we can build a
             // simplex; otherwise, go to the next depth.
             if let Some(v) = depth.get(self.1) {
-                let mut index = *v;
-                let mut vertices = vec![];
-                loop {
-                    // If we've made it to the root return the simplex.
-                    if index == 0 {
-                        self.1 = self.1 + 1;
-                        vertices.reverse();
-                        return Some(Simplex::new(vertices));
-                    } else {
-                        vertices.push(*self.2.arena.get(index).unwrap());
-                        index = *self.2.parent.get(index).unwrap();
-                    }
-                }
+                self.1 += 1;
+                Some(Simplex::new(self.2.path(*v)))
             } else {
                 // If it's a dimensional iter, we're done.
                 if self.3 {
                     return None;
                 }
 
-                self.0 = self.0 + 1;
+                self.0 += 1;
                 self.1 = 0;
-                return self.next();
+                self.next()
             }
         } else {
             None
@@ -195,32 +269,32 @@ mod tests {
         let mut trie: TrieArena<Vertex> = TrieArena::new();
 
         // 0-degree simplex at the root.
-        let simplex = Simplex::new(vec![Vertex::new(1, 0)]);
+        let simplex = Simplex::new(vec![Vertex::new(1, 0.0)]);
         trie.add(simplex.vertices());
         assert_eq!(trie.contains(simplex.vertices()), true);
 
         // 0-degree simplex not contained within the trie.
-        let simplex = Simplex::new(vec![Vertex::new(2, 0)]);
+        let simplex = Simplex::new(vec![Vertex::new(2, 0.0)]);
         assert_eq!(trie.contains(simplex.vertices()), false);
 
         // 2-degree simplex.
         let simplex = Simplex::new(vec![
-            Vertex::new(1, 0),
-            Vertex::new(2, 1),
-            Vertex::new(3, 1),
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 1.0),
+            Vertex::new(3, 1.0),
         ]);
         trie.add(simplex.vertices());
         assert_eq!(trie.contains(simplex.vertices()), true);
 
         // 1-degree simplex contained within the above 3-degree simplex.
-        let simplex = Simplex::new(vec![Vertex::new(1, 0), Vertex::new(2, 1)]);
+        let simplex = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 1.0)]);
         assert_eq!(trie.contains(simplex.vertices()), true);
     }
 
     #[test]
     fn test_simplex_triearena_iter() {
         let mut st = SimplexTrie::new_skel(10);
-        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0), Vertex::new(2, 3)]);
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
         st.add_simplex(&smplx_1);
 
         // 11-simplices (10 0-skel, 1 1-skel).
@@ -241,7 +315,7 @@ mod tests {
         for i in 0..10 {
             let value = st_iter.next();
             assert_eq!(value.is_some(), true);
-            assert_eq!(value.unwrap(), Simplex::new(vec![Vertex::new(i, 0)]));
+            assert_eq!(value.unwrap(), Simplex::new(vec![Vertex::new(i, 0.0)]));
         }
 
         // No more simplices.
@@ -252,12 +326,12 @@ mod tests {
     fn test_simplex_trie_iter_dim_1_skel() {
         let mut st = SimplexTrie::new_skel(10);
 
-        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0), Vertex::new(2, 3)]);
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
         st.add_simplex(&smplx_1);
         let smplx_2 = Simplex::new(vec![
-            Vertex::new(1, 0),
-            Vertex::new(2, 3),
-            Vertex::new(3, 4),
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 3.0),
+            Vertex::new(3, 4.0),
         ]);
         st.add_simplex(&smplx_2);
 
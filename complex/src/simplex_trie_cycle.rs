@@ -1,20 +1,347 @@
-// src/complex/src/simplex_trie_cyclic.rs
+// src/complex/src/simplex_trie_cycle.rs
+//! A simplex tree whose nodes each hold a link to their parent, and which
+//! keeps a list of every node sharing a label; the cofaces of a simplex are
+//! found from the nodes labelled with its vertices, walking upward.
 //! Referenced from: 'The Simplex Tree: An Efficient Data Structure for
 //! General Simplicial Complexes - Jean-Daniel Boissonnat · Clément Maria'
+use crate::simplex::{Simplex, Vertex};
+use crate::storage::{facets, ids, link};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::{Rc, Weak};
 
-struct Node<T> {
-    children: BTreeMap<T, Node<T>>,
-    parent: Option<Node<T>>,
+type Link = Rc<RefCell<Node>>;
+
+struct Node {
+    vertex: Vertex,
+    // The number of vertices on the path from the root, inclusive.
+    depth: usize,
+    // Keyed by the id of each child's vertex; its weight is held within.
+    children: BTreeMap<usize, Link>,
+    // Held weakly; a parent owns its children.
+    parent: Weak<RefCell<Node>>,
+}
+
+impl Node {
+    fn new(vertex: Vertex, depth: usize, parent: Weak<RefCell<Node>>) -> Link {
+        Rc::new(RefCell::new(Node {
+            vertex,
+            depth,
+            children: BTreeMap::new(),
+            parent,
+        }))
+    }
+}
+
+/// Return the vertices on the path from the root to 'node'.
+fn path(node: &Link) -> Vec<Vertex> {
+    let mut r = vec![];
+    let mut index = Rc::clone(node);
+    loop {
+        let parent = index.borrow().parent.upgrade();
+        match parent {
+            Some(parent) => {
+                r.push(index.borrow().vertex);
+                index = parent;
+            }
+            None => break,
+        }
+    }
+
+    r.reverse();
+    r
 }
 
-impl<T> Node<T>
-where
-    T: Ord + Copy,
-{
-    pub fn new(parent: Option<Node<T>>) -> Self {
+/// Push the simplices of 'node' and everything beneath it onto 'r'; 'prefix'
+/// holds the path to 'node'.
+fn subtree(node: &Link, prefix: &mut Vec<Vertex>, r: &mut Vec<Simplex>) {
+    r.push(Simplex::new(prefix.clone()));
+    for child in node.borrow().children.values() {
+        prefix.push(child.borrow().vertex);
+        subtree(child, prefix, r);
+        prefix.pop();
     }
 }
 
-struct SimplexTrie {
-    root: Node<Vertex>,
+/// Find the node below 'node' whose path through it holds exactly the
+/// vertices 'ids', in whichever order they were added.
+fn locate(node: &Link, ids: &[usize]) -> Option<Link> {
+    if ids.is_empty() {
+        return Some(Rc::clone(node));
+    }
+
+    let node = node.borrow();
+    ids.iter().find_map(|id| {
+        let child = node.children.get(id)?;
+        let rest: Vec<usize> = ids.iter().copied().filter(|i| i != id).collect();
+        locate(child, &rest)
+    })
+}
+
+pub struct SimplexTrie {
+    root: Link,
+    // Every node, by the id of its label.
+    labels: BTreeMap<usize, Vec<Link>>,
+}
+
+impl Default for SimplexTrie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimplexTrie {
+    /// Create a new, empty SimplexTrie.
+    pub fn new() -> Self {
+        SimplexTrie {
+            root: Node::new(Vertex::default(), 0, Weak::new()),
+            labels: BTreeMap::new(),
+        }
+    }
+
+    /// Create a new SimplexTrie with a 0-skeleton of size 'sz'.
+    pub fn new_skel(sz: usize) -> Self {
+        let mut st = SimplexTrie::new();
+        for i in 0..sz {
+            st.add_simplex(&Simplex::new(vec![Vertex::new(i, 0.0)]));
+        }
+
+        st
+    }
+
+    /// Add a simplex; nodes are matched by the id of their vertex alone, so
+    /// a simplex added again with other weights updates its path.
+    pub fn add_simplex(&mut self, simplex: &Simplex) {
+        let mut node = Rc::clone(&self.root);
+        for (depth, vertex) in simplex.into_iter().enumerate() {
+            let child = node.borrow().children.get(&vertex.id()).cloned();
+            node = match child {
+                Some(child) => {
+                    child.borrow_mut().vertex = *vertex;
+                    child
+                }
+                None => {
+                    let child = Node::new(*vertex, depth + 1, Rc::downgrade(&node));
+                    node.borrow_mut()
+                        .children
+                        .insert(vertex.id(), Rc::clone(&child));
+                    self.labels
+                        .entry(vertex.id())
+                        .or_default()
+                        .push(Rc::clone(&child));
+                    child
+                }
+            };
+        }
+    }
+
+    pub fn contains_simplex(&self, simplex: &Simplex) -> bool {
+        let mut node = Rc::clone(&self.root);
+        for vertex in simplex {
+            let child = node.borrow().children.get(&vertex.id()).cloned();
+            match child {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Return the simplex with exactly the vertices 'ids', as it was added.
+    pub fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        match ids.is_empty() {
+            true => None,
+            false => locate(&self.root, ids).map(|node| Simplex::new(path(&node))),
+        }
+    }
+
+    /// Return the facets of a simplex, its faces of one dimension lower.
+    pub fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        facets(simplex, |ids| self.locate(ids))
+    }
+
+    /// Return the proper cofaces of a simplex. Each lies beneath a node
+    /// labelled with one of its vertices, the deepest of the simplex along
+    /// its path; only those nodes are visited, walking upward from each.
+    pub fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let ids = ids(simplex);
+        let mut r = vec![];
+        for (id, nodes) in ids
+            .iter()
+            .filter_map(|i| self.labels.get(i).map(|n| (i, n)))
+        {
+            for node in nodes.iter().filter(|n| n.borrow().depth >= ids.len()) {
+                let mut prefix = path(node);
+                let above = &prefix[..prefix.len() - 1];
+                if ids
+                    .iter()
+                    .filter(|i| *i != id)
+                    .all(|i| above.iter().any(|v| v.id() == *i))
+                {
+                    let start = r.len();
+                    subtree(node, &mut prefix, &mut r);
+
+                    // The node itself is the simplex.
+                    if node.borrow().depth == ids.len() {
+                        r.remove(start);
+                    }
+                }
+            }
+        }
+
+        r
+    }
+
+    /// Return the star of a simplex; the simplex and its cofaces.
+    pub fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let mut r: Vec<Simplex> = self.locate(&ids(simplex)).into_iter().collect();
+        r.extend(self.cofaces(simplex));
+        r
+    }
+
+    /// Return the link of a simplex; every simplex disjoint from it whose
+    /// union with it is a simplex.
+    pub fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        link(simplex, &self.cofaces(simplex), |ids| self.locate(ids))
+    }
+
+    /// Return the number of simplices; every node is within a label list.
+    pub fn count(&self) -> usize {
+        self.labels.values().map(|nodes| nodes.len()).sum()
+    }
+
+    /// Iterate, returning simplices of dimension 'sz' ordered by their last
+    /// vertex; each is found from the label lists, walking upward.
+    pub fn iter_dim(&self, sz: usize) -> SimplexTrieIterator {
+        let simplices: Vec<Simplex> = self
+            .labels
+            .values()
+            .flatten()
+            .filter(|node| node.borrow().depth == sz + 1)
+            .map(|node| Simplex::new(path(node)))
+            .collect();
+
+        SimplexTrieIterator(simplices.into_iter())
+    }
+}
+
+/// The simplices are gathered up front; a node can't be borrowed across
+/// calls to `next`.
+pub struct SimplexTrieIterator(std::vec::IntoIter<Simplex>);
+
+impl Iterator for SimplexTrieIterator {
+    type Item = Simplex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl IntoIterator for &SimplexTrie {
+    type Item = Simplex;
+    type IntoIter = SimplexTrieIterator;
+
+    /// Iterate breadth first, by dimension.
+    fn into_iter(self) -> Self::IntoIter {
+        let mut r = vec![];
+        let mut queue: VecDeque<(Link, Vec<Vertex>)> = VecDeque::new();
+        queue.push_back((Rc::clone(&self.root), vec![]));
+
+        while let Some((node, prefix)) = queue.pop_front() {
+            for child in node.borrow().children.values() {
+                let mut path = prefix.clone();
+                path.push(child.borrow().vertex);
+                r.push(Simplex::new(path.clone()));
+                queue.push_back((Rc::clone(child), path));
+            }
+        }
+
+        SimplexTrieIterator(r.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplex_trie_add_contains() {
+        let mut st = SimplexTrie::new();
+
+        let simplex = Simplex::new(vec![
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 1.0),
+            Vertex::new(3, 1.0),
+        ]);
+        st.add_simplex(&simplex);
+        assert!(st.contains_simplex(&simplex));
+
+        let face = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 1.0)]);
+        assert!(st.contains_simplex(&face));
+
+        let simplex = Simplex::new(vec![Vertex::new(2, 0.0)]);
+        assert!(!st.contains_simplex(&simplex));
+    }
+
+    #[test]
+    fn test_simplex_trie_parent() {
+        let mut st = SimplexTrie::new();
+        let simplex = Simplex::new(vec![
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 1.0),
+            Vertex::new(3, 1.0),
+        ]);
+        st.add_simplex(&simplex);
+
+        // Each label is found once; its path leads back to the root.
+        let node = &st.labels[&3][0];
+        assert_eq!(node.borrow().depth, 3);
+        assert_eq!(Simplex::new(path(node)), simplex);
+    }
+
+    #[test]
+    fn test_simplex_trie_iter() {
+        let mut st = SimplexTrie::new_skel(10);
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
+        st.add_simplex(&smplx_1);
+
+        // 11-simplices (10 0-skel, 1 1-skel), lowest dimension first.
+        let simplices: Vec<Simplex> = st.into_iter().collect();
+        assert_eq!(simplices.len(), 11);
+        assert_eq!(simplices[10], smplx_1);
+    }
+
+    #[test]
+    fn test_simplex_trie_iter_dim_0_skel() {
+        let st = SimplexTrie::new_skel(10);
+        let mut st_iter = st.iter_dim(0);
+
+        // Iteration on the dimension 0, should return 10 entries.
+        for i in 0..10 {
+            let value = st_iter.next();
+            assert!(value.is_some());
+            assert_eq!(value.unwrap(), Simplex::new(vec![Vertex::new(i, 0.0)]));
+        }
+
+        // No more simplices.
+        assert!(st_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_simplex_trie_iter_dim_1_skel() {
+        let mut st = SimplexTrie::new_skel(10);
+
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
+        st.add_simplex(&smplx_1);
+        let smplx_2 = Simplex::new(vec![
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 3.0),
+            Vertex::new(3, 4.0),
+        ]);
+        st.add_simplex(&smplx_2);
+
+        let simplices: Vec<Simplex> = st.iter_dim(1).collect();
+        assert_eq!(simplices, vec![smplx_1]);
+    }
 }
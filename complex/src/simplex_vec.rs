@@ -0,0 +1,143 @@
+// src/complex/src/simplex_vec.rs
+//! A flat backend; the simplices of each dimension are held in a vector,
+//! sorted by their vertices as the paths of a trie would be. Lookups are a
+//! binary search, and iteration is a walk over contiguous memory.
+use crate::simplex::{Simplex, Vertex};
+use std::cmp::Ordering;
+
+/// Compare two simplices by the ids of their vertices alone, as the paths of
+/// a trie keyed on ids are ordered.
+fn by_ids(a: &[Vertex], b: &[Vertex]) -> Ordering {
+    a.iter().map(|v| v.id()).cmp(b.iter().map(|v| v.id()))
+}
+
+#[derive(Default)]
+pub struct SimplexVec(Vec<Vec<Vec<Vertex>>>);
+
+impl SimplexVec {
+    /// Create a new, empty SimplexVec.
+    pub fn new() -> Self {
+        SimplexVec(Vec::new())
+    }
+
+    /// Add a simplex, along with each prefix of its vertices; a simplex
+    /// already held on the same ids takes the weights given.
+    pub fn add_simplex(&mut self, simplex: &Simplex) {
+        let vertices = simplex.vertices();
+        if self.0.len() < vertices.len() {
+            self.0.resize(vertices.len(), vec![]);
+        }
+
+        for (i, dim) in self.0.iter_mut().enumerate().take(vertices.len()) {
+            let prefix = &vertices[..=i];
+            match dim.binary_search_by(|s| by_ids(s, prefix)) {
+                Ok(at) => dim[at].copy_from_slice(prefix),
+                Err(at) => dim.insert(at, prefix.to_vec()),
+            }
+        }
+    }
+
+    pub fn contains_simplex(&self, simplex: &Simplex) -> bool {
+        let vertices = simplex.vertices();
+        match vertices.len().checked_sub(1).and_then(|d| self.0.get(d)) {
+            Some(dim) => dim.binary_search_by(|s| by_ids(s, vertices)).is_ok(),
+            None => vertices.is_empty(),
+        }
+    }
+
+    /// Return the number of simplices.
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|dim| dim.len()).sum()
+    }
+
+    /// Return the number of simplices of dimension 'sz'.
+    pub fn count_dim(&self, sz: usize) -> usize {
+        self.0.get(sz).map_or(0, |dim| dim.len())
+    }
+
+    /// Iterate, returning simplices of dimension 'sz'.
+    pub fn iter_dim(&self, sz: usize) -> SimplexVecIterator<'_> {
+        let dims = match self.0.get(sz) {
+            Some(_) => &self.0[sz..=sz],
+            None => &[],
+        };
+
+        SimplexVecIterator(dims, 0)
+    }
+}
+
+// Dimensions remaining, Index.
+pub struct SimplexVecIterator<'a>(&'a [Vec<Vec<Vertex>>], usize);
+
+impl<'a> Iterator for SimplexVecIterator<'a> {
+    type Item = Simplex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (dim, rest) = self.0.split_first()?;
+        match dim.get(self.1) {
+            Some(vertices) => {
+                self.1 += 1;
+                Some(Simplex::new(vertices.clone()))
+            }
+            None => {
+                self.0 = rest;
+                self.1 = 0;
+                self.next()
+            }
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a SimplexVec {
+    type Item = Simplex;
+    type IntoIter = SimplexVecIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SimplexVecIterator(&self.0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplex_vec_add_contains() {
+        let mut sv = SimplexVec::new();
+        let simplex = Simplex::new(vec![
+            Vertex::new(1, 0.0),
+            Vertex::new(2, 1.0),
+            Vertex::new(3, 1.0),
+        ]);
+        sv.add_simplex(&simplex);
+        assert!(sv.contains_simplex(&simplex));
+        assert!(sv.contains_simplex(&Simplex::new(vec![Vertex::new(1, 0.0)])));
+        assert!(!sv.contains_simplex(&Simplex::new(vec![Vertex::new(2, 1.0)])));
+        assert_eq!(sv.count(), 3);
+
+        // Adding a simplex again changes nothing.
+        sv.add_simplex(&simplex);
+        assert_eq!(sv.count(), 3);
+    }
+
+    #[test]
+    fn test_simplex_vec_iter() {
+        let mut sv = SimplexVec::new();
+        for i in (0..10).rev() {
+            sv.add_simplex(&Simplex::new(vec![Vertex::new(i, 0.0)]));
+        }
+        let smplx_1 = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(2, 3.0)]);
+        sv.add_simplex(&smplx_1);
+
+        // Ordered as the trie orders them, whatever the order added.
+        let vertices: Vec<Simplex> = sv.iter_dim(0).collect();
+        let expected: Vec<Simplex> = (0..10)
+            .map(|i| Simplex::new(vec![Vertex::new(i, 0.0)]))
+            .collect();
+        assert_eq!(vertices, expected);
+
+        assert_eq!(sv.into_iter().count(), 11);
+        assert_eq!(sv.iter_dim(1).collect::<Vec<Simplex>>(), vec![smplx_1]);
+        assert_eq!(sv.iter_dim(2).count(), 0);
+    }
+}
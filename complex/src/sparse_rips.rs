@@ -0,0 +1,217 @@
+// src/complex/src/sparse_rips.rs
+//! Utilities for computing a sparse approximation of the Vietoris-Rips
+//! filtration. Points are ordered by a greedy permutation; once the scale
+//! passes a multiple of its insertion radius, a point's neighborhood is
+//! covered by earlier points and it stops gaining edges. The resulting graph
+//! is far smaller than the full one, while its persistence diagram is within
+//! a (1 + ε) multiplicative factor of the full filtration's (on a log scale),
+//! for 0 < ε < 1.
+//! Referenced from: 'Linear-Size Approximations to the Vietoris-Rips Filtration - Donald R. Sheehy'
+//! Referenced from: 'A Geometric Perspective on Sparse Filtrations - Nicholas J. Cavanna · Mahmoodreza Jahanseir · Donald R. Sheehy'
+use crate::simplex_trie::SimplexTrie;
+use crate::vietoris_rips::{inductive, Edge};
+use crate::{SimplicialComplex, Storage};
+use common::{sparse::SparseMatrix, Matrix};
+use std::marker::PhantomData;
+
+/// Return the distance between 'a' and 'b'; missing entries are treated as
+/// infinitely far apart.
+fn dist<T: Edge, M: Matrix<T>>(dm: &M, a: usize, b: usize) -> f64 {
+    match a == b {
+        true => 0.0,
+        false => dm
+            .get(a, b)
+            .and_then(|val| val.length())
+            .unwrap_or(f64::INFINITY),
+    }
+}
+
+/// Compute a greedy (farthest-point) permutation of the points of a distance
+/// matrix, beginning with the first. Each point is returned with its
+/// insertion radius, the distance to the points before it; the first is
+/// infinite, and the remainder are non-increasing.
+///
+/// `distance::subsample::farthest_point` selects the same points, but reports
+/// only the final covering radius rather than each insertion radius, and
+/// reads distances from a point cloud or a dense matrix of floats rather than
+/// a matrix of edges with missing entries; nor does this crate depend upon
+/// `distance`.
+pub fn greedy_permutation<T: Edge, M: Matrix<T>>(dm: &M) -> Vec<(usize, f64)> {
+    let n = dm.dim().0;
+    let mut r = Vec::with_capacity(n);
+    // The distance from each point to the nearest point already placed.
+    let mut nearest = vec![f64::INFINITY; n];
+    let mut placed = vec![false; n];
+
+    let mut next = 0;
+    for _ in 0..n {
+        r.push((next, nearest[next]));
+        placed[next] = true;
+
+        for (i, d) in nearest.iter_mut().enumerate() {
+            *d = d.min(dist(dm, next, i));
+        }
+
+        // The furthest unplaced point, ties broken by index.
+        next = match (0..n)
+            .filter(|i| !placed[*i])
+            .max_by(|a, b| nearest[*a].total_cmp(&nearest[*b]).then(b.cmp(a)))
+        {
+            Some(i) => i,
+            None => break,
+        };
+    }
+
+    r
+}
+
+/// Return the filtration value of an edge of length 'len' between points
+/// with insertion radii 'a' >= 'b', or None if their balls never meet. The
+/// ball of a point with insertion radius λ grows with the scale until it
+/// stops at radius λ(1 + ε) / ε; the edge enters once the two balls meet.
+///
+/// The points still growing at scale (1 + ε)α, those with λ >= εα, are a
+/// prefix of the greedy permutation, so every other point lies within εα of
+/// one of them; an edge of length at most 2α in the full filtration then maps
+/// to one of length at most 2(1 + ε)α between them, and the filtrations are
+/// (1 + ε)-interleaved. Were the balls to stop at λ / ε instead, the points
+/// growing at scale cα would only cover within εcα, and the factor would
+/// weaken to 1 / (1 - ε).
+fn sparse_edge(len: f64, a: f64, b: f64, epsilon: f64) -> Option<f64> {
+    let stop = |lambda: f64| lambda * (1.0 + epsilon) / epsilon;
+    if len <= 2.0 * stop(b) {
+        Some(len)
+    } else if len <= stop(a) + stop(b) {
+        Some(2.0 * (len - stop(b)))
+    } else {
+        None
+    }
+}
+
+/// Computes sparse Rips complexes held within the storage backend 'S'.
+pub struct SparseRips<S = SimplexTrie>(PhantomData<S>);
+
+impl<S: Storage> SparseRips<S> {
+    /// Compute the sparse Rips filtration of a distance matrix up to
+    /// dimension 'dim', with the approximation parameter 'epsilon'. As with
+    /// `VietorisRips::compute_weighted`, a simplex enters with its longest
+    /// edge; an edge may enter later than its length, but never earlier.
+    /// Panics unless 0 < 'epsilon' < 1.
+    pub fn compute<T: Edge, M: Matrix<T>>(
+        dm: &M,
+        epsilon: f64,
+        dim: usize,
+    ) -> SimplicialComplex<S> {
+        if epsilon <= 0.0 || epsilon >= 1.0 {
+            panic!("epsilon must lie within (0, 1)!");
+        }
+
+        let (rows, cols) = dm.dim();
+        let mut radii = vec![0.0; rows];
+        for (i, lambda) in greedy_permutation(dm) {
+            radii[i] = lambda;
+        }
+
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(rows, cols);
+        for a in 0..rows {
+            for b in a + 1..cols {
+                let (hi, lo) = match radii[a] >= radii[b] {
+                    true => (radii[a], radii[b]),
+                    false => (radii[b], radii[a]),
+                };
+
+                if let Some(val) = sparse_edge(dist(dm, a, b), hi, lo, epsilon) {
+                    adj.set(a, b, val);
+                    adj.set(b, a, val);
+                }
+            }
+        }
+
+        inductive(&adj, dim, None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Simplex;
+    use crate::vietoris_rips::VietorisRips;
+    use common::dense::DenseMatrix;
+    use rand::prelude::*;
+
+    /// The Euclidean distance matrix of 'n' random points in the unit square.
+    fn dist_mat(n: usize) -> DenseMatrix<f64> {
+        let mut rng = StdRng::seed_from_u64(3);
+        let cloud: Vec<(f64, f64)> = (0..n).map(|_| (rng.gen(), rng.gen())).collect();
+
+        let mut dm = DenseMatrix::new(n, n);
+        for (i, a) in cloud.iter().enumerate() {
+            for (j, b) in cloud.iter().enumerate() {
+                dm.set(i, j, ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt());
+            }
+        }
+
+        dm
+    }
+
+    #[test]
+    fn test_greedy_permutation() {
+        let mut dm: DenseMatrix<f64> = DenseMatrix::new(4, 4);
+        let line = [0.0, 1.0, 3.0, 7.0];
+        for i in 0..4 {
+            for j in 0..4 {
+                dm.set(i, j, f64::abs(line[i] - line[j]));
+            }
+        }
+
+        let perm = greedy_permutation(&dm);
+        assert_eq!(perm, vec![(0, f64::INFINITY), (3, 7.0), (2, 3.0), (1, 1.0)]);
+
+        let dm = dist_mat(100);
+        let perm = greedy_permutation(&dm);
+        assert_eq!(perm.len(), 100);
+        assert!(perm.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn test_sparse_rips() {
+        let dm = dist_mat(150);
+        let full: SimplicialComplex = VietorisRips::compute_weighted(None, &dm, 2);
+        let sparse: SimplicialComplex = SparseRips::compute(&dm, 0.5, 2);
+
+        let full_edges: Vec<Simplex> = full.iter_dim(1).collect();
+        let sparse_edges: Vec<Simplex> = sparse.iter_dim(1).collect();
+        assert_eq!(sparse.iter_dim(0).count(), 150);
+        assert!(sparse_edges.len() * 2 < full_edges.len());
+
+        // An edge never enters the filtration before its length.
+        for edge in &sparse_edges {
+            let ids: Vec<usize> = edge.into_iter().map(|v| v.id()).collect();
+            assert!(edge.weight() >= *dm.get(ids[0], ids[1]).unwrap());
+        }
+
+        // A smaller epsilon retains more edges.
+        let dense: SimplicialComplex = SparseRips::compute(&dm, 0.1, 2);
+        assert!(dense.iter_dim(1).count() > sparse_edges.len());
+    }
+
+    #[test]
+    fn test_sparse_rips_small_epsilon() {
+        // Every pair is alive together, so the full filtration is recovered.
+        let dm = dist_mat(20);
+        let full: SimplicialComplex = VietorisRips::compute_weighted(None, &dm, 3);
+        let sparse: SimplicialComplex = SparseRips::compute(&dm, 1e-9, 3);
+
+        for d in 0..3 {
+            let a: Vec<Simplex> = full.iter_dim(d).collect();
+            let b: Vec<Simplex> = sparse.iter_dim(d).collect();
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sparse_rips_invalid_epsilon() {
+        let _: SimplicialComplex = SparseRips::compute(&dist_mat(4), 1.0, 2);
+    }
+}
@@ -0,0 +1,458 @@
+// src/complex/src/storage.rs
+//! The storage backing a SimplicialComplex. Each backend holds a set of
+//! simplices closed under prefixes; adding a simplex adds every prefix of
+//! its vertices, as a path within a trie would.
+use crate::simplex::Simplex;
+use crate::{simplex_trie, simplex_trie_arena, simplex_trie_cycle, simplex_vec};
+
+pub trait Storage: Default {
+    type Iter<'a>: Iterator<Item = Simplex>
+    where
+        Self: 'a;
+
+    fn add_simplex(&mut self, simplex: &Simplex);
+    fn contains_simplex(&self, simplex: &Simplex) -> bool;
+
+    /// Iterate over every simplex, lowest dimension first.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Iterate, returning simplices of dimension 'sz'.
+    fn iter_dim(&self, sz: usize) -> Self::Iter<'_>;
+
+    /// Return the number of simplices.
+    fn count(&self) -> usize;
+
+    /// Return the number of simplices of dimension 'sz'.
+    fn count_dim(&self, sz: usize) -> usize {
+        self.iter_dim(sz).count()
+    }
+
+    /// Add the simplices of another backend to this one.
+    fn merge(&mut self, other: Self) {
+        for simplex in other.iter() {
+            self.add_simplex(&simplex);
+        }
+    }
+
+    /// Build a backend from a `simplex_trie::SimplexTrie`, as is filled
+    /// across threads.
+    fn from_trie(trie: simplex_trie::SimplexTrie) -> Self {
+        let mut r = Self::default();
+        for simplex in &trie {
+            r.add_simplex(&simplex);
+        }
+
+        r
+    }
+
+    /// Return the simplex with exactly the vertices 'ids', as it was added.
+    fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        let sz = ids.len().checked_sub(1)?;
+        self.iter_dim(sz)
+            .find(|s| s.into_iter().all(|v| ids.contains(&v.id())))
+    }
+
+    /// Return the index of the simplex with exactly the vertices 'ids'
+    /// within its dimension; by default its position within `iter_dim`,
+    /// which may shift as others are added.
+    fn index(&self, ids: &[usize]) -> Option<usize> {
+        let sz = ids.len().checked_sub(1)?;
+        self.iter_dim(sz)
+            .position(|s| s.into_iter().all(|v| ids.contains(&v.id())))
+    }
+
+    /// Remove a simplex along with its cofaces; by default the backend is
+    /// rebuilt from the simplices left.
+    fn remove_simplex(&mut self, simplex: &Simplex) {
+        let ids = ids(simplex);
+        let mut r = Self::default();
+        for s in self
+            .iter()
+            .filter(|s| !ids.iter().all(|i| s.into_iter().any(|v| v.id() == *i)))
+        {
+            r.add_simplex(&s);
+        }
+
+        *self = r;
+    }
+
+    /// Return the facets of a simplex, its faces of one dimension lower.
+    fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        facets(simplex, |ids| self.locate(ids))
+    }
+
+    /// Return the proper cofaces of a simplex, every simplex containing it.
+    fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let ids = ids(simplex);
+        self.iter()
+            .filter(|s| s.dim() >= ids.len())
+            .filter(|s| ids.iter().all(|i| s.into_iter().any(|v| v.id() == *i)))
+            .collect()
+    }
+
+    /// Return the star of a simplex; the simplex and its cofaces.
+    fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        let mut r: Vec<Simplex> = self.locate(&ids(simplex)).into_iter().collect();
+        r.extend(self.cofaces(simplex));
+        r
+    }
+
+    /// Return the link of a simplex; every simplex disjoint from it whose
+    /// union with it is a simplex.
+    fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        link(simplex, &self.cofaces(simplex), |ids| self.locate(ids))
+    }
+}
+
+/// Return the ids of the vertices of a simplex.
+pub(crate) fn ids(simplex: &Simplex) -> Vec<usize> {
+    simplex.into_iter().map(|v| v.id()).collect()
+}
+
+/// Return the facets of a simplex found by 'locate'.
+pub(crate) fn facets<F>(simplex: &Simplex, locate: F) -> Vec<Simplex>
+where
+    F: Fn(&[usize]) -> Option<Simplex>,
+{
+    let ids = ids(simplex);
+    match ids.len() {
+        1 => vec![],
+        _ => (0..ids.len())
+            .filter_map(|k| locate(&[&ids[..k], &ids[k + 1..]].concat()))
+            .collect(),
+    }
+}
+
+/// Return the link of a simplex from its cofaces, each simplex of the link
+/// found by 'locate'.
+pub(crate) fn link<F>(simplex: &Simplex, cofaces: &[Simplex], locate: F) -> Vec<Simplex>
+where
+    F: Fn(&[usize]) -> Option<Simplex>,
+{
+    let ids = ids(simplex);
+    cofaces
+        .iter()
+        .filter_map(|c| {
+            let rest: Vec<usize> = c
+                .into_iter()
+                .map(|v| v.id())
+                .filter(|i| !ids.contains(i))
+                .collect();
+            locate(&rest)
+        })
+        .collect()
+}
+
+impl Storage for simplex_trie::SimplexTrie {
+    type Iter<'a> = simplex_trie::SimplexTrieIterator<'a>;
+
+    fn add_simplex(&mut self, simplex: &Simplex) {
+        self.add_simplex(simplex)
+    }
+
+    fn contains_simplex(&self, simplex: &Simplex) -> bool {
+        self.contains_simplex(simplex)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.into_iter()
+    }
+
+    fn iter_dim(&self, sz: usize) -> Self::Iter<'_> {
+        self.iter_dim(sz)
+    }
+
+    fn count(&self) -> usize {
+        self.count()
+    }
+
+    fn count_dim(&self, sz: usize) -> usize {
+        self.count_dim(sz)
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.merge(other)
+    }
+
+    fn from_trie(trie: simplex_trie::SimplexTrie) -> Self {
+        trie
+    }
+
+    fn index(&self, ids: &[usize]) -> Option<usize> {
+        self.index(ids)
+    }
+
+    fn remove_simplex(&mut self, simplex: &Simplex) {
+        self.remove_simplex(simplex)
+    }
+
+    fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        self.locate(ids)
+    }
+
+    fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.facets(simplex)
+    }
+
+    fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.cofaces(simplex)
+    }
+
+    fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.star(simplex)
+    }
+
+    fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.link(simplex)
+    }
+}
+
+impl Storage for simplex_trie_arena::SimplexTrie {
+    type Iter<'a> = simplex_trie_arena::SimplexTrieIterator<'a>;
+
+    fn add_simplex(&mut self, simplex: &Simplex) {
+        self.add_simplex(simplex)
+    }
+
+    fn contains_simplex(&self, simplex: &Simplex) -> bool {
+        self.contains_simplex(simplex)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.into_iter()
+    }
+
+    fn iter_dim(&self, sz: usize) -> Self::Iter<'_> {
+        self.iter_dim(sz)
+    }
+
+    fn count(&self) -> usize {
+        self.count()
+    }
+
+    fn count_dim(&self, sz: usize) -> usize {
+        self.count_dim(sz)
+    }
+
+    fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        self.locate(ids)
+    }
+
+    fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.facets(simplex)
+    }
+
+    fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.cofaces(simplex)
+    }
+
+    fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.star(simplex)
+    }
+
+    fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.link(simplex)
+    }
+}
+
+impl Storage for simplex_trie_cycle::SimplexTrie {
+    type Iter<'a> = simplex_trie_cycle::SimplexTrieIterator;
+
+    fn add_simplex(&mut self, simplex: &Simplex) {
+        self.add_simplex(simplex)
+    }
+
+    fn contains_simplex(&self, simplex: &Simplex) -> bool {
+        self.contains_simplex(simplex)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.into_iter()
+    }
+
+    fn iter_dim(&self, sz: usize) -> Self::Iter<'_> {
+        self.iter_dim(sz)
+    }
+
+    fn count(&self) -> usize {
+        self.count()
+    }
+
+    fn locate(&self, ids: &[usize]) -> Option<Simplex> {
+        self.locate(ids)
+    }
+
+    fn facets(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.facets(simplex)
+    }
+
+    fn cofaces(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.cofaces(simplex)
+    }
+
+    fn star(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.star(simplex)
+    }
+
+    fn link(&self, simplex: &Simplex) -> Vec<Simplex> {
+        self.link(simplex)
+    }
+}
+
+impl Storage for simplex_vec::SimplexVec {
+    type Iter<'a> = simplex_vec::SimplexVecIterator<'a>;
+
+    fn add_simplex(&mut self, simplex: &Simplex) {
+        self.add_simplex(simplex)
+    }
+
+    fn contains_simplex(&self, simplex: &Simplex) -> bool {
+        self.contains_simplex(simplex)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.into_iter()
+    }
+
+    fn iter_dim(&self, sz: usize) -> Self::Iter<'_> {
+        self.iter_dim(sz)
+    }
+
+    fn count(&self) -> usize {
+        self.count()
+    }
+
+    fn count_dim(&self, sz: usize) -> usize {
+        self.count_dim(sz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Vertex;
+
+    /// Fill a backend with a triangle and a pendant edge.
+    fn fill<S: Storage>() -> S {
+        let mut st = S::default();
+        let v = Vertex::new;
+        for smplx in [
+            vec![v(4, 0.0)],
+            vec![v(2, 0.0), v(1, 1.5), v(0, 2.0)],
+            vec![v(2, 0.0), v(0, 2.0)],
+            vec![v(1, 0.0), v(0, 1.0)],
+            vec![v(3, 0.0), v(2, 0.5)],
+        ] {
+            st.add_simplex(&Simplex::new(smplx));
+        }
+
+        st
+    }
+
+    fn check<S: Storage>() {
+        let st: S = fill();
+        let triangle = Simplex::new(vec![
+            Vertex::new(2, 0.0),
+            Vertex::new(1, 1.5),
+            Vertex::new(0, 2.0),
+        ]);
+        assert!(st.contains_simplex(&triangle));
+        assert!(st.contains_simplex(&Simplex::new(vec![Vertex::new(2, 0.0)])));
+        assert!(!st.contains_simplex(&Simplex::new(vec![Vertex::new(0, 0.0)])));
+
+        // Prefixes of each simplex: vertices 1, 2, 3 and 4, edges (2, 1),
+        // (2, 0), (1, 0) and (3, 2), and the triangle.
+        assert_eq!(st.count(), 9);
+        assert_eq!(st.iter().count(), 9);
+        assert_eq!(st.iter_dim(0).count(), 4);
+        assert_eq!(st.iter_dim(1).count(), 4);
+        assert_eq!(st.iter_dim(2).collect::<Vec<Simplex>>(), vec![triangle]);
+        assert_eq!(st.iter_dim(3).count(), 0);
+        for sz in 0..4 {
+            assert_eq!(st.count_dim(sz), st.iter_dim(sz).count());
+        }
+
+        // Lowest dimension first.
+        let dims: Vec<usize> = st.iter().map(|s| s.dim()).collect();
+        assert!(dims.windows(2).all(|w| w[0] <= w[1]));
+
+        // A simplex added again with other weights is updated in place,
+        // rather than held a second time.
+        let mut updated: S = fill();
+        let edge = Simplex::new(vec![Vertex::new(3, 0.0), Vertex::new(2, 0.75)]);
+        updated.add_simplex(&edge);
+        assert_eq!(updated.count(), 9);
+        assert_eq!(updated.count_dim(1), 4);
+        assert!(updated.contains_simplex(&edge));
+        assert_eq!(updated.iter_dim(1).filter(|s| *s == edge).count(), 1);
+        assert!(updated.iter_dim(1).all(|s| s.weight() != 0.5));
+
+        let mut merged: S = fill();
+        let mut other = S::default();
+        other.add_simplex(&Simplex::new(vec![
+            Vertex::new(5, 0.0),
+            Vertex::new(4, 1.0),
+        ]));
+        merged.merge(other);
+        assert_eq!(merged.count(), 11);
+
+        // Queries about the simplices around another, by their ids.
+        let sorted = |simplices: Vec<Simplex>| -> Vec<Vec<usize>> {
+            let mut r: Vec<Vec<usize>> = simplices
+                .iter()
+                .map(|s| {
+                    let mut ids = ids(s);
+                    ids.sort_unstable();
+                    ids
+                })
+                .collect();
+            r.sort();
+            r
+        };
+        let vertex = |i| Simplex::new(vec![Vertex::new(i, 0.0)]);
+        let triangle = st.iter_dim(2).next().unwrap();
+        assert_eq!(st.locate(&[0, 1, 2]), Some(triangle.clone()));
+        assert_eq!(st.locate(&[2, 0, 1]), Some(triangle.clone()));
+        assert_eq!(st.locate(&[0, 3]), None);
+        assert_eq!(st.locate(&[]), None);
+        assert!(st.index(&[1, 2]).is_some_and(|i| i < 4));
+        assert_eq!(st.index(&[0, 3]), None);
+        assert_eq!(
+            sorted(st.facets(&triangle)),
+            vec![vec![0, 1], vec![0, 2], vec![1, 2]]
+        );
+        assert_eq!(
+            sorted(st.cofaces(&vertex(2))),
+            vec![vec![0, 1, 2], vec![0, 2], vec![1, 2], vec![2, 3]]
+        );
+        assert!(st.facets(&vertex(4)).is_empty());
+        assert_eq!(
+            st.cofaces(&st.locate(&[0, 1]).unwrap()),
+            vec![triangle.clone()]
+        );
+        assert!(st.cofaces(&triangle).is_empty());
+
+        // The star leads with the simplex itself.
+        assert_eq!(sorted(st.star(&vertex(3))), vec![vec![2, 3], vec![3]]);
+        assert_eq!(st.star(&vertex(2)).len(), 5);
+        assert_eq!(st.star(&vertex(2))[0], vertex(2));
+        assert_eq!(st.link(&vertex(3)), vec![vertex(2)]);
+        assert!(st.link(&st.locate(&[2, 3]).unwrap()).is_empty());
+        assert!(st.link(&vertex(4)).is_empty());
+
+        let mut removed: S = fill();
+        removed.remove_simplex(&vertex(2));
+        assert_eq!(
+            sorted(removed.iter().collect()),
+            vec![vec![0, 1], vec![1], vec![3], vec![4]]
+        );
+    }
+
+    #[test]
+    fn test_storage() {
+        check::<simplex_trie::SimplexTrie>();
+        check::<simplex_trie_arena::SimplexTrie>();
+        check::<simplex_trie_cycle::SimplexTrie>();
+        check::<simplex_vec::SimplexVec>();
+    }
+}
@@ -0,0 +1,207 @@
+// src/complex/src/triangulations.rs
+//! Triangulations of standard spaces, for testing and teaching; each is
+//! given along with its Betti numbers over Z2 and Euler characteristic.
+//! Every simplex enters the filtration at zero.
+use crate::builder::ComplexBuilder;
+use crate::SimplicialComplex;
+
+/// A triangulation of a known space, with its Betti numbers over Z2 from
+/// dimension zero up to that of the space, and its Euler characteristic.
+pub struct Triangulation {
+    pub complex: SimplicialComplex,
+    pub betti: Vec<usize>,
+    pub euler: i64,
+}
+
+impl Triangulation {
+    fn new(maximal: Vec<Vec<usize>>, betti: Vec<usize>) -> Self {
+        let euler = betti
+            .iter()
+            .enumerate()
+            .map(|(k, b)| match k % 2 {
+                0 => *b as i64,
+                _ => -(*b as i64),
+            })
+            .sum();
+
+        Triangulation {
+            complex: ComplexBuilder::new().maximal(maximal).unwrap().build(),
+            betti,
+            euler,
+        }
+    }
+}
+
+/// Return the ids of each 'k'-subset of 0..n, in lexicographic order.
+fn subsets(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+
+    (k - 1..n)
+        .flat_map(|last| {
+            subsets(last, k - 1).into_iter().map(move |mut s| {
+                s.push(last);
+                s
+            })
+        })
+        .collect()
+}
+
+/// The 'n'-simplex, filled; contractible.
+pub fn simplex(n: usize) -> Triangulation {
+    let mut betti = vec![0; n + 1];
+    betti[0] = 1;
+    Triangulation::new(vec![(0..=n).collect()], betti)
+}
+
+/// The 'n'-sphere, as the boundary of the (n + 1)-simplex.
+pub fn sphere(n: usize) -> Triangulation {
+    let mut betti = vec![0; n + 1];
+    betti[0] += 1;
+    betti[n] += 1;
+    Triangulation::new(subsets(n + 2, n + 1), betti)
+}
+
+/// The torus, on seven vertices; each pair of vertices shares an edge.
+/// Referenced from: 'Sur le problème des sept couleurs - Ákos Császár'
+pub fn torus() -> Triangulation {
+    let maximal = (0..7)
+        .flat_map(|i| {
+            [
+                vec![i, (i + 1) % 7, (i + 3) % 7],
+                vec![i, (i + 2) % 7, (i + 3) % 7],
+            ]
+        })
+        .collect();
+    Triangulation::new(maximal, vec![1, 2, 1])
+}
+
+/// The Klein bottle, as a three by three grid of squares whose sides are
+/// glued, one pair with a twist.
+pub fn klein_bottle() -> Triangulation {
+    let id = |i: usize, j: usize| match i {
+        3 => (3 - j % 3) % 3,
+        _ => i * 3 + j % 3,
+    };
+
+    let maximal = (0..3)
+        .flat_map(|i| (0..3).map(move |j| (i, j)))
+        .flat_map(|(i, j)| {
+            let (a, b, c, d) = (id(i, j), id(i + 1, j), id(i, j + 1), id(i + 1, j + 1));
+            [vec![a, b, d], vec![a, c, d]]
+        })
+        .collect();
+    Triangulation::new(maximal, vec![1, 2, 1])
+}
+
+/// The real projective plane on six vertices; the antipodal quotient of the
+/// icosahedron.
+pub fn projective_plane() -> Triangulation {
+    let maximal = [
+        [0, 1, 2],
+        [0, 2, 3],
+        [0, 3, 4],
+        [0, 4, 5],
+        [0, 5, 1],
+        [1, 2, 4],
+        [2, 3, 5],
+        [3, 4, 1],
+        [4, 5, 2],
+        [5, 1, 3],
+    ];
+    Triangulation::new(maximal.iter().map(|t| t.to_vec()).collect(), vec![1, 1, 1])
+}
+
+/// The Möbius strip on five vertices, a band of triangles closed with a
+/// twist.
+pub fn mobius_strip() -> Triangulation {
+    let maximal = (0..5).map(|i| vec![i, (i + 1) % 5, (i + 2) % 5]).collect();
+    Triangulation::new(maximal, vec![1, 1, 0])
+}
+
+/// A wedge of 'k' circles, each a hollow triangle through vertex 0.
+pub fn wedge(k: usize) -> Triangulation {
+    if k == 0 {
+        panic!("the number of circles must be non-zero!");
+    }
+
+    let maximal = (0..k)
+        .flat_map(|c| {
+            let (a, b) = (2 * c + 1, 2 * c + 2);
+            [vec![0, a], vec![a, b], vec![b, 0]]
+        })
+        .collect();
+    Triangulation::new(maximal, vec![1, k])
+}
+
+/// The dunce hat; a triangle whose sides are all glued in the same
+/// direction. Contractible, yet has no free face to collapse from. The
+/// sides are split at the vertices 0, 1 and 2, and the disc within is
+/// coned off through a ring of nine vertices to a centre.
+/// Referenced from: 'On the Dunce Hat - E. C. Zeeman'
+pub fn dunce_hat() -> Triangulation {
+    // The boundary of the disc, read around it; the third side is reversed.
+    let boundary = [0, 1, 2, 0, 1, 2, 0, 2, 1];
+    let ring = |k: usize| 3 + k % 9;
+
+    let maximal = (0..9)
+        .flat_map(|k| {
+            let (a, b) = (boundary[k], boundary[(k + 1) % 9]);
+            [
+                vec![a, b, ring(k)],
+                vec![b, ring(k), ring(k + 1)],
+                vec![12, ring(k), ring(k + 1)],
+            ]
+        })
+        .collect();
+    Triangulation::new(maximal, vec![1, 0, 0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsets() {
+        assert_eq!(
+            subsets(4, 3),
+            vec![vec![0, 1, 2], vec![0, 1, 3], vec![0, 2, 3], vec![1, 2, 3]]
+        );
+        assert_eq!(subsets(3, 0), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn test_triangulations() {
+        let all = [
+            simplex(0),
+            simplex(3),
+            sphere(0),
+            sphere(1),
+            sphere(2),
+            torus(),
+            klein_bottle(),
+            projective_plane(),
+            mobius_strip(),
+            wedge(3),
+            dunce_hat(),
+        ];
+
+        for t in &all {
+            assert_eq!(t.complex.validate(), Ok(()));
+            assert_eq!(t.complex.euler(), t.euler);
+            assert_eq!(t.complex.dim(), t.betti.len());
+        }
+
+        // Vertices, edges and triangles of the closed surfaces.
+        let counts =
+            |t: &Triangulation| -> Vec<usize> { (0..3).map(|k| t.complex.count_dim(k)).collect() };
+        assert_eq!(counts(&all[5]), vec![7, 21, 14]);
+        assert_eq!(counts(&all[6]), vec![9, 27, 18]);
+        assert_eq!(counts(&all[7]), vec![6, 15, 10]);
+
+        assert_eq!(all[2].complex.count(), 2);
+        assert_eq!(all[2].euler, 2);
+        assert_eq!(all[9].euler, -2);
+    }
+}
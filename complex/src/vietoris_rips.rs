@@ -4,23 +4,130 @@
 //! of the Vietoris-Rips Complex - Afria Zomorodian".
 use crate::simplex::{Simplex, Vertex};
 use crate::simplex_trie::SimplexTrie;
-use crate::SimplicialComplex;
-use common::Matrix;
-use std::collections::HashSet;
+use crate::{SimplicialComplex, Storage};
+use common::{float::Float, sparse::SparseMatrix, Matrix};
+use std::cmp::Ordering;
+use std::marker::PhantomData;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Enumeration for each algorithm computing a Vietoris-Rips complex; each
+/// produces an identical complex.
 pub enum VRAlgorithm {
+    /// Extend every simplex of one dimension to the next, a dimension at a
+    /// time.
     Inductive,
+    /// Add the cofaces of each vertex in turn, depth first.
+    Incremental,
+    /// Add the faces of each maximal clique.
+    Maximal,
+    /// Add the cofaces of each vertex across threads.
+    #[cfg(feature = "parallel")]
+    Parallel,
+}
+
+/// An entry within an adjacency matrix; whether it is an edge, and if so
+/// its length.
+pub trait Edge {
+    fn length(&self) -> Option<f64>;
+}
+
+impl Edge for bool {
+    fn length(&self) -> Option<f64> {
+        match self {
+            true => Some(0.0),
+            false => None,
+        }
+    }
+}
+
+impl Edge for f64 {
+    fn length(&self) -> Option<f64> {
+        Some(*self)
+    }
+}
+
+/// Widening an f32 length to f64 is exact; the filtration holds precisely
+/// the values of the adjacency matrix.
+impl Edge for f32 {
+    fn length(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
+}
+
+/// Return the length of the edge (a, b), if it exists.
+fn edge_length<T: Edge, M: Matrix<T>>(adj: &M, a: usize, b: usize) -> Option<f64> {
+    adj.get(a, b).and_then(|val| val.length())
 }
 
 /// Find all neighbors of a vertex within the adjacency neighborhood.
-fn lower_nbrs<M: Matrix<bool>>(adj: &M, vertex: &Vertex) -> Vec<usize> {
+fn lower_nbrs<T: Edge, M: Matrix<T>>(adj: &M, vertex: &Vertex) -> Vec<usize> {
     let mut r = Vec::new();
     let (_, cols) = adj.dim();
 
     for i in 0..cols {
-        if let Some(val) = adj.get(vertex.id(), i) {
-            if *val && vertex.id() > i {
-                r.push(i);
+        if edge_length(adj, vertex.id(), i).is_some() && vertex.id() > i {
+            r.push(i);
+        }
+    }
+
+    r
+}
+
+/// The lower neighbors of every vertex, each ordered by id, with the length
+/// of each such edge; each row of the adjacency matrix is scanned once up
+/// front rather than for every vertex of every simplex.
+struct Lower {
+    nbrs: Vec<Vec<usize>>,
+    lengths: Vec<Vec<f64>>,
+}
+
+impl Lower {
+    fn new<T: Edge, M: Matrix<T>>(adj: &M) -> Self {
+        let nbrs: Vec<Vec<usize>> = (0..adj.dim().0)
+            .map(|i| lower_nbrs(adj, &Vertex::new(i, 0.0)))
+            .collect();
+        let lengths = nbrs
+            .iter()
+            .enumerate()
+            .map(|(i, ln)| {
+                ln.iter()
+                    .map(|j| edge_length(adj, i, *j).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+
+        Lower { nbrs, lengths }
+    }
+
+    /// Return the number of vertices.
+    fn len(&self) -> usize {
+        self.nbrs.len()
+    }
+
+    /// Return the length of the edge (a, b), where 'b' is a lower neighbor
+    /// of 'a'.
+    fn length(&self, a: usize, b: usize) -> f64 {
+        match self.nbrs[a].binary_search(&b) {
+            Ok(i) => self.lengths[a][i],
+            Err(_) => 0.0,
+        }
+    }
+}
+
+/// Return the intersection of two sets of ids, each ordered by id.
+fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut r = Vec::with_capacity(a.len().min(b.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                r.push(a[i]);
+                i += 1;
+                j += 1;
             }
         }
     }
@@ -28,13 +135,37 @@ fn lower_nbrs<M: Matrix<bool>>(adj: &M, vertex: &Vertex) -> Vec<usize> {
     r
 }
 
-// TODO: Investigate whether the HashSets can be more efficiently utilized in
-// this function; I have a feeling there is a lot of copying, re-initializing,
-// etc.
+/// Return the 0-simplex of vertex 'i', entering with its value within
+/// 'vertices', or zero.
+fn vertex(i: usize, vertices: Option<&[f64]>) -> Simplex {
+    Simplex::new(vec![Vertex::new(i, vertices.map_or(0.0, |v| v[i]))])
+}
+
+/// Glue the vertex 'uv' onto 'simplex'; the new vertex carries the weight of
+/// the resulting simplex, its longest edge. If 'weight' is given it replaces
+/// the length of every edge.
+fn glue(lower: &Lower, simplex: &Simplex, uv: usize, weight: Option<f64>) -> Simplex {
+    let w = simplex.into_iter().fold(simplex.weight(), |acc, v| {
+        acc.max(weight.unwrap_or(lower.length(v.id(), uv)))
+    });
+
+    let mut vertices: Vec<Vertex> = simplex.clone().into_iter().collect();
+    vertices.push(Vertex::new(uv, w));
+    Simplex::new(vertices)
+}
+
 /// Compute a Vietoris-Rips complex up to dimension 'dim' with an inductive
-/// algorithm.
-fn inductive<M: Matrix<bool>>(adj: &M, dim: usize, weight: usize) -> SimplicialComplex {
-    let mut sc = SimplicialComplex(SimplexTrie::new(), dim);
+/// algorithm. A simplex enters the filtration with its longest edge; if
+/// 'weight' is given it replaces the length of every edge. Vertices enter
+/// with their value within 'vertices', or zero.
+pub(crate) fn inductive<S: Storage, T: Edge, M: Matrix<T>>(
+    adj: &M,
+    dim: usize,
+    weight: Option<f64>,
+    vertices: Option<&[f64]>,
+) -> SimplicialComplex<S> {
+    let mut sc = SimplicialComplex(S::default(), dim);
+    let lower = Lower::new(adj);
 
     // See `ITERATOR_ISSUE.md` for concerns about utilizing an iterator within
     // this function; utilizing two vectors is likely more performant.
@@ -42,64 +173,304 @@ fn inductive<M: Matrix<bool>>(adj: &M, dim: usize, weight: usize) -> SimplicialC
     let mut k1: Vec<Simplex> = vec![];
 
     // Compute the 0-skeleton.
-    for i in 0..adj.dim().0 {
-        let smplx = Simplex::new(vec![Vertex::new(i, 0)]);
+    for i in 0..lower.len() {
+        let smplx = vertex(i, vertices);
         sc.0.add_simplex(&smplx);
         k.push(smplx);
     }
 
     for _ in 1..dim {
         for simplex in &k {
-            // TODO: The borrow checker throws a fit if this isn't initialized
-            // here. Is this a bug?
-            let mut shared_vertices: HashSet<usize> = HashSet::new();
-            for (i, vertex) in simplex.into_iter().enumerate() {
-                let ln = lower_nbrs(adj, vertex);
-                match i {
-                    // 0, shared_vertices is the union.
-                    0 => {
-                        shared_vertices = HashSet::from_iter(ln);
-                    }
-                    // 1, shared_vertices is the intersection.
-                    _ => {
-                        let tmp: HashSet<usize> = HashSet::from_iter(ln);
-                        shared_vertices = tmp.intersection(&shared_vertices).copied().collect();
-                    }
-                }
+            // The lower neighbors shared by every vertex of the simplex.
+            let mut shared_vertices = lower.nbrs[simplex.vertices()[0].id()].clone();
+            for vertex in simplex.into_iter().skip(1) {
+                shared_vertices = intersect(&shared_vertices, &lower.nbrs[vertex.id()]);
             }
 
             // Glue each shared vertex to the current simplex.
             for uv in shared_vertices {
-                let mut vertices: Vec<Vertex> = simplex.clone().into_iter().collect();
-                vertices.push(Vertex::new(uv, weight));
-                let smplx = Simplex::new(vertices);
-
+                let smplx = glue(&lower, simplex, uv, weight);
                 sc.0.add_simplex(&smplx);
                 k1.push(smplx);
             }
         }
 
         // k1 becomes k, k1 is cleared.
-        k = k1.clone();
-        k1.clear();
+        k = std::mem::take(&mut k1);
     }
 
     sc
 }
 
-pub struct VietorisRips;
+/// Add 'simplex' and each of its cofaces up to 'dim' vertices to 'trie';
+/// 'nbrs' are the lower neighbors shared by each of its vertices.
+fn add_cofaces<S: Storage>(
+    trie: &mut S,
+    lower: &Lower,
+    dim: usize,
+    weight: Option<f64>,
+    simplex: Simplex,
+    nbrs: &[usize],
+) {
+    trie.add_simplex(&simplex);
+    if simplex.vertices().len() >= dim {
+        return;
+    }
 
-impl VietorisRips {
-    /// Compute a Vietoris-Rips diagram from an adjacency matrix.
+    for uv in nbrs {
+        let smplx = glue(lower, &simplex, *uv, weight);
+        let shared = intersect(nbrs, &lower.nbrs[*uv]);
+        add_cofaces(trie, lower, dim, weight, smplx, &shared);
+    }
+}
+
+/// Compute a Vietoris-Rips complex with the incremental algorithm; each
+/// vertex in turn adds every simplex for which it is the highest vertex,
+/// depth first. Arguments are as with `inductive`.
+pub(crate) fn incremental<S: Storage, T: Edge, M: Matrix<T>>(
+    adj: &M,
+    dim: usize,
+    weight: Option<f64>,
+    vertices: Option<&[f64]>,
+) -> SimplicialComplex<S> {
+    let mut trie = S::default();
+    let lower = Lower::new(adj);
+
+    for (i, nbrs) in lower.nbrs.iter().enumerate() {
+        add_cofaces(&mut trie, &lower, dim, weight, vertex(i, vertices), nbrs);
+    }
+
+    SimplicialComplex(trie, dim)
+}
+
+/// Compute a Vietoris-Rips complex with the incremental algorithm across
+/// threads. The work is partitioned by the vertex each simplex is expanded
+/// from, its highest; every simplex lies in exactly one partition, rooted at
+/// that vertex within the trie. Each thread fills a trie of its own, and the
+/// tries are merged; as a trie is ordered by its contents rather than by
+/// insertion, the result is identical for any number of threads. The merged
+/// trie is then moved into the backend. Arguments are as with `inductive`.
+#[cfg(feature = "parallel")]
+pub(crate) fn parallel<S: Storage, T: Edge, M: Matrix<T>>(
+    adj: &M,
+    dim: usize,
+    weight: Option<f64>,
+    vertices: Option<&[f64]>,
+) -> SimplicialComplex<S> {
+    let lower = Lower::new(adj);
+
+    let trie = (0..lower.len())
+        .into_par_iter()
+        .fold(SimplexTrie::new, |mut trie, i| {
+            let nbrs = &lower.nbrs[i];
+            add_cofaces(&mut trie, &lower, dim, weight, vertex(i, vertices), nbrs);
+            trie
+        })
+        .reduce(SimplexTrie::new, |mut a, b| {
+            a.merge(b);
+            a
+        });
+
+    SimplicialComplex(S::from_trie(trie), dim)
+}
+
+/// Enumerate the maximal cliques containing 'r', extended by vertices of 'p'
+/// and excluding those of 'x', with Bron–Kerbosch pivoting on the vertex of
+/// 'p' or 'x' with the most neighbors in 'p'.
+fn bron_kerbosch(
+    nbrs: &[Vec<usize>],
+    r: &mut Vec<usize>,
+    mut p: Vec<usize>,
+    mut x: Vec<usize>,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    let pivot = match p
+        .iter()
+        .chain(&x)
+        .max_by_key(|u| intersect(&p, &nbrs[**u]).len())
+    {
+        Some(u) => *u,
+        None => {
+            cliques.push(r.clone());
+            return;
+        }
+    };
+
+    for v in intersect_complement(&p, &nbrs[pivot]) {
+        r.push(v);
+        bron_kerbosch(
+            nbrs,
+            r,
+            intersect(&p, &nbrs[v]),
+            intersect(&x, &nbrs[v]),
+            cliques,
+        );
+        r.pop();
+
+        p.retain(|u| *u != v);
+        if let Err(i) = x.binary_search(&v) {
+            x.insert(i, v);
+        }
+    }
+}
+
+/// Return the ids of 'a' absent from 'b', each ordered by id.
+fn intersect_complement(a: &[usize], b: &[usize]) -> Vec<usize> {
+    a.iter()
+        .filter(|u| b.binary_search(u).is_err())
+        .copied()
+        .collect()
+}
+
+/// Add every simplex extending 'simplex' by vertices of 'clique', ordered by
+/// decreasing id, up to 'dim' vertices.
+fn add_faces<S: Storage>(
+    trie: &mut S,
+    lower: &Lower,
+    dim: usize,
+    weight: Option<f64>,
+    clique: &[usize],
+    simplex: &Simplex,
+) {
+    if simplex.vertices().len() >= dim {
+        return;
+    }
+
+    for (i, uv) in clique.iter().enumerate() {
+        let smplx = glue(lower, simplex, *uv, weight);
+        trie.add_simplex(&smplx);
+        add_faces(trie, lower, dim, weight, &clique[i + 1..], &smplx);
+    }
+}
+
+/// Compute a Vietoris-Rips complex from the maximal cliques of the graph,
+/// found with the Bron–Kerbosch algorithm; every face of each clique, up to
+/// 'dim' vertices, is added. Arguments are as with `inductive`.
+pub(crate) fn maximal<S: Storage, T: Edge, M: Matrix<T>>(
+    adj: &M,
+    dim: usize,
+    weight: Option<f64>,
+    vertices: Option<&[f64]>,
+) -> SimplicialComplex<S> {
+    let mut trie = S::default();
+    let lower = Lower::new(adj);
+
+    // Both lower and upper neighbors, each ordered by id.
+    let mut nbrs = lower.nbrs.clone();
+    for (i, ln) in lower.nbrs.iter().enumerate() {
+        for j in ln {
+            nbrs[*j].push(i);
+        }
+    }
+
+    let mut cliques = vec![];
+    let all: Vec<usize> = (0..lower.len()).collect();
+    bron_kerbosch(&nbrs, &mut vec![], all, vec![], &mut cliques);
+
+    // An isolated vertex is a clique of its own.
+    for mut clique in cliques {
+        // Vertices of a simplex are ordered by decreasing id, as with the
+        // other algorithms, so that identical simplices share a path.
+        clique.sort_unstable_by(|a, b| b.cmp(a));
+        for (i, v) in clique.iter().enumerate() {
+            let smplx = vertex(*v, vertices);
+            trie.add_simplex(&smplx);
+            add_faces(&mut trie, &lower, dim, weight, &clique[i + 1..], &smplx);
+        }
+    }
+
+    SimplicialComplex(trie, dim)
+}
+
+/// Return the filtration value of an edge of length 'len' between vertices
+/// with the DTM values 'a' and 'b' (p = 1); the radius at which the balls of
+/// radius t - a and t - b first meet.
+fn dtm_edge(len: f64, a: f64, b: f64) -> f64 {
+    match len <= (a - b).abs() {
+        true => a.max(b),
+        false => (a + b + len) / 2.0,
+    }
+}
+
+/// Computes Vietoris-Rips complexes held within the storage backend 'S'.
+pub struct VietorisRips<S = SimplexTrie>(PhantomData<S>);
+
+impl<S: Storage> VietorisRips<S> {
+    /// Compute a Vietoris-Rips diagram from an adjacency matrix; every
+    /// simplex above the 0-skeleton is given the weight 'weight'.
     pub fn compute<M: Matrix<bool>>(
         alg: Option<VRAlgorithm>,
         adj: &M,
         dim: usize,
-        weight: usize,
-    ) -> SimplicialComplex {
+        weight: f64,
+    ) -> SimplicialComplex<S> {
         let alg = alg.unwrap_or(VRAlgorithm::Inductive);
         match alg {
-            VRAlgorithm::Inductive => inductive(adj, dim, weight),
+            VRAlgorithm::Inductive => inductive(adj, dim, Some(weight), None),
+            VRAlgorithm::Incremental => incremental(adj, dim, Some(weight), None),
+            VRAlgorithm::Maximal => maximal(adj, dim, Some(weight), None),
+            #[cfg(feature = "parallel")]
+            VRAlgorithm::Parallel => parallel(adj, dim, Some(weight), None),
+        }
+    }
+
+    /// Compute a Vietoris-Rips diagram from a weighted adjacency matrix,
+    /// where each present entry is the length of an edge; a simplex is
+    /// weighted by its longest edge. Lengths computed in f32 carry less
+    /// precision, so distinct f64 distances may round to the same f32
+    /// value; such simplices tie within the filtration and are then ordered
+    /// by vertex id.
+    pub fn compute_weighted<F: Float + Edge, M: Matrix<F>>(
+        alg: Option<VRAlgorithm>,
+        adj: &M,
+        dim: usize,
+    ) -> SimplicialComplex<S> {
+        let alg = alg.unwrap_or(VRAlgorithm::Inductive);
+        match alg {
+            VRAlgorithm::Inductive => inductive(adj, dim, None, None),
+            VRAlgorithm::Incremental => incremental(adj, dim, None, None),
+            VRAlgorithm::Maximal => maximal(adj, dim, None, None),
+            #[cfg(feature = "parallel")]
+            VRAlgorithm::Parallel => parallel(adj, dim, None, None),
+        }
+    }
+
+    /// Compute a DTM-weighted Vietoris-Rips filtration from a weighted
+    /// adjacency matrix and the distance-to-measure of each vertex, such as
+    /// `distance::density::dtm`. A vertex enters at its DTM value, so
+    /// outliers enter late and no longer close small spurious loops. Values
+    /// are radii rather than diameters; between vertices with a DTM of zero,
+    /// an edge enters at half its length. Panics unless there is a DTM value
+    /// for each vertex.
+    /// Referenced from: 'DTM-based Filtrations - Hirokazu Anai · Frédéric Chazal · Marc Glisse · Yuichi Ike · Hiroya Inakoshi · Raphaël Tinarrage · Yuhei Umeda'
+    pub fn compute_dtm<F: Float + Edge, M: Matrix<F>>(
+        alg: Option<VRAlgorithm>,
+        adj: &M,
+        dtm: &[F],
+        dim: usize,
+    ) -> SimplicialComplex<S> {
+        let (rows, cols) = adj.dim();
+        if dtm.len() != rows {
+            panic!("a DTM value is required for each vertex!");
+        }
+
+        let dtm: Vec<f64> = dtm.iter().map(|x| (*x).into()).collect();
+        let mut values: SparseMatrix<f64> = SparseMatrix::new(rows, cols);
+        for r in 0..rows {
+            for c in 0..cols {
+                if let Some(len) = edge_length(adj, r, c) {
+                    values.set(r, c, dtm_edge(len, dtm[r], dtm[c]));
+                }
+            }
+        }
+
+        let alg = alg.unwrap_or(VRAlgorithm::Inductive);
+        match alg {
+            VRAlgorithm::Inductive => inductive(&values, dim, None, Some(&dtm)),
+            VRAlgorithm::Incremental => incremental(&values, dim, None, Some(&dtm)),
+            VRAlgorithm::Maximal => maximal(&values, dim, None, Some(&dtm)),
+            #[cfg(feature = "parallel")]
+            VRAlgorithm::Parallel => parallel(&values, dim, None, Some(&dtm)),
         }
     }
 }
@@ -107,7 +478,8 @@ impl VietorisRips {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use common::dense::DenseMatrix;
+    use common::{dense::DenseMatrix, edge_list::EdgeList, sparse::SparseMatrix};
+    use rand::prelude::*;
 
     #[test]
     fn test_lower_nbrs() {
@@ -128,15 +500,15 @@ mod tests {
         }
 
         // 0 == false.
-        let ln = lower_nbrs(&adj, &Vertex::new(0, 0));
+        let ln = lower_nbrs(&adj, &Vertex::new(0, 0.0));
         assert_eq!(ln, vec![]);
 
         // (9 + even) % 2 == true.
-        let ln = lower_nbrs(&adj, &Vertex::new(9, 0));
+        let ln = lower_nbrs(&adj, &Vertex::new(9, 0.0));
         assert_eq!(ln, vec![0, 2, 4, 6, 8]);
 
         // (4 + odd) % 2 == true.
-        let ln = lower_nbrs(&adj, &Vertex::new(4, 0));
+        let ln = lower_nbrs(&adj, &Vertex::new(4, 0.0));
         assert_eq!(ln, vec![1, 3]);
     }
 
@@ -158,11 +530,11 @@ mod tests {
             }
         }
 
-        let complex = inductive(&adj, 3, 0);
+        let complex: SimplicialComplex = inductive(&adj, 3, Some(0.0), None);
 
         // The 0-skeleton should be within the complex.
         for i in 0..4 {
-            let smplx = Simplex::new(vec![Vertex::new(i, 0)]);
+            let smplx = Simplex::new(vec![Vertex::new(i, 0.0)]);
             assert_eq!(complex.0.contains_simplex(&smplx), true);
         }
 
@@ -171,13 +543,13 @@ mod tests {
         // 1 1 0 1 0
         // 2 0 1 0 1
         // 3 1 0 1 0
-        let smplx = Simplex::new(vec![Vertex::new(1, 0), Vertex::new(0, 0)]);
+        let smplx = Simplex::new(vec![Vertex::new(1, 0.0), Vertex::new(0, 0.0)]);
         assert_eq!(complex.0.contains_simplex(&smplx), true);
 
-        let smplx = Simplex::new(vec![Vertex::new(3, 0), Vertex::new(2, 0)]);
+        let smplx = Simplex::new(vec![Vertex::new(3, 0.0), Vertex::new(2, 0.0)]);
         assert_eq!(complex.0.contains_simplex(&smplx), true);
 
-        let smplx = Simplex::new(vec![Vertex::new(3, 0), Vertex::new(0, 0)]);
+        let smplx = Simplex::new(vec![Vertex::new(3, 0.0), Vertex::new(0, 0.0)]);
         assert_eq!(complex.0.contains_simplex(&smplx), true);
     }
 
@@ -200,11 +572,297 @@ mod tests {
         }
 
         // complex_1 should be a subset of complex_2.
-        let complex_1 = inductive(&adj, 4, 0);
-        let complex_2 = inductive(&adj, 5, 0);
+        let complex_1: SimplicialComplex = inductive(&adj, 4, Some(0.0), None);
+        let complex_2: SimplicialComplex = inductive(&adj, 5, Some(0.0), None);
 
         for smplx in &complex_1.0 {
             assert_eq!(complex_2.0.contains_simplex(&smplx), true);
         }
     }
+
+    #[test]
+    fn test_compute_weighted() {
+        // A triangle with sides of length 1, 2 and 3.
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(3, 3);
+        for (a, b, len) in [(0, 1, 1.0), (0, 2, 2.0), (1, 2, 3.0)] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 3);
+
+        // Each edge carries its length; the triangle its longest edge.
+        let mut weights: Vec<f64> = complex.iter_dim(1).map(|s| s.weight()).collect();
+        weights.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(weights, vec![1.0, 2.0, 3.0]);
+
+        let triangles: Vec<Simplex> = complex.iter_dim(2).collect();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0].weight(), 3.0);
+    }
+
+    #[test]
+    fn test_compute_weighted_f32() {
+        let mut adj: SparseMatrix<f32> = SparseMatrix::new(3, 3);
+        for (a, b, len) in [(0, 1, 0.1f32), (0, 2, 0.2), (1, 2, 0.3)] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        // The widened f32 values are retained exactly.
+        let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 3);
+        let triangles: Vec<Simplex> = complex.iter_dim(2).collect();
+        assert_eq!(triangles[0].weight(), 0.3f32 as f64);
+    }
+
+    #[test]
+    fn test_compute_weighted_edge_list() {
+        let mut adj: EdgeList<f64> = EdgeList::new(4, 4);
+        for (a, b, len) in [(0, 1, 0.1), (0, 2, 0.2), (1, 2, 0.3), (2, 3, 0.4)] {
+            adj.set(a, b, len);
+        }
+
+        // An edge list is symmetric, so each edge is only set once.
+        let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 3);
+        assert_eq!(complex.iter_dim(1).count(), 4);
+        let triangles: Vec<Simplex> = complex.iter_dim(2).collect();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0].weight(), 0.3);
+    }
+
+    #[test]
+    fn test_compute_weighted_tiled() {
+        use distance::neighborhood::to_weighted_adjacency;
+        use distance::point_cloud::to_dist_mat;
+        use distance::tiled::TiledDistance;
+
+        let mut rng = StdRng::seed_from_u64(37);
+        let cloud: Vec<Vec<f64>> = (0..40)
+            .map(|_| (0..3).map(|_| rng.gen()).collect())
+            .collect();
+
+        // The streamed edge list builds the same complex as the dense matrix.
+        let edges = TiledDistance::new(&cloud, None, 7).edges_below(0.4);
+        let adj = to_weighted_adjacency(&to_dist_mat(&cloud, None), 0.4);
+        for dim in 1..5 {
+            let tiled: SimplicialComplex = VietorisRips::compute_weighted(None, &edges, dim);
+            let dense: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, dim);
+            assert_eq!(simplices(&tiled), simplices(&dense));
+            assert!(dense.count_dim(dim - 1) > 0);
+        }
+    }
+
+    #[test]
+    fn test_compute_dtm() {
+        // A triangle of side 1, and an outlier 0.6 from each of its vertices
+        // with a large DTM value.
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(4, 4);
+        for (a, b, len) in [
+            (0, 1, 1.0),
+            (0, 2, 1.0),
+            (1, 2, 1.0),
+            (0, 3, 0.6),
+            (1, 3, 0.6),
+            (2, 3, 0.6),
+        ] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        let complex: SimplicialComplex =
+            VietorisRips::compute_dtm(None, &adj, &[0.1, 0.1, 0.1, 2.0], 3);
+        let vertices: Vec<f64> = complex.iter_dim(0).map(|s| s.weight()).collect();
+        assert_eq!(vertices, vec![0.1, 0.1, 0.1, 2.0]);
+
+        // The outlier's edges are dominated by its DTM value, while the
+        // triangle's edges enter at (0.1 + 0.1 + 1.0) / 2.
+        let mut edges: Vec<f64> = complex.iter_dim(1).map(|s| s.weight()).collect();
+        edges.sort_by(f64::total_cmp);
+        assert_eq!(edges, vec![0.6, 0.6, 0.6, 2.0, 2.0, 2.0]);
+
+        let triangles: Vec<f64> = complex.iter_dim(2).map(|s| s.weight()).collect();
+        assert_eq!(triangles.len(), 4);
+        assert_eq!(triangles.iter().filter(|w| **w == 0.6).count(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compute_dtm_missing_values() {
+        let adj: SparseMatrix<f64> = SparseMatrix::new(4, 4);
+        VietorisRips::<SimplexTrie>::compute_dtm(None, &adj, &[0.0], 2);
+    }
+
+    #[test]
+    fn test_compute_knn_graphs() {
+        use distance::neighborhood::{to_cknn_adjacency, to_knn_adjacency, KnnGraph};
+
+        // Points on a line at 0, 3, 4, 10; nearest neighbors 0 -> 1, 1 -> 2,
+        // 2 -> 1, 3 -> 2.
+        let cloud = vec![vec![0.0], vec![3.0], vec![4.0], vec![10.0]];
+        let edges = |adj: &SparseMatrix<bool>| -> Vec<Vec<usize>> {
+            let complex: SimplicialComplex = VietorisRips::compute(None, adj, 3, 0.0);
+            assert_eq!(complex.count_dim(0), 4);
+            assert_eq!(complex.count_dim(2), 0);
+            let mut r: Vec<Vec<usize>> = complex
+                .iter_dim(1)
+                .map(|s| s.into_iter().map(|v| v.id()).collect())
+                .collect();
+            r.sort();
+            r
+        };
+
+        let symmetric = to_knn_adjacency(&cloud, 1, Some(KnnGraph::Symmetric), None);
+        assert_eq!(edges(&symmetric), vec![vec![1, 0], vec![2, 1], vec![3, 2]]);
+
+        let mutual = to_knn_adjacency(&cloud, 1, Some(KnnGraph::Mutual), None);
+        assert_eq!(edges(&mutual), vec![vec![2, 1]]);
+
+        // d_k = 3, 1, 1, 6; only 0 ~ 1 and 1 ~ 2 are within
+        // 2 * sqrt(d_k(i) * d_k(j)).
+        let cknn = to_cknn_adjacency(&cloud, 1, 2.0, None);
+        assert_eq!(edges(&cknn), vec![vec![1, 0], vec![2, 1]]);
+
+        // The directed graph holds 0 -> 1 above the diagonal alone, so the
+        // edge is lost, as documented on KnnGraph::Directed.
+        let directed = to_knn_adjacency(&cloud, 1, Some(KnnGraph::Directed), None);
+        assert_eq!(edges(&directed), vec![vec![2, 1], vec![3, 2]]);
+    }
+
+    /// Collect every simplex of a complex, in iteration order.
+    fn simplices(complex: &SimplicialComplex) -> Vec<Simplex> {
+        complex.into_iter().collect()
+    }
+
+    #[test]
+    fn test_algorithms_identical() {
+        let mut rng = StdRng::seed_from_u64(29);
+        for _ in 0..10 {
+            let n = rng.gen_range(1..30);
+            let mut adj: SparseMatrix<f64> = SparseMatrix::new(n, n);
+            for a in 0..n {
+                for b in 0..a {
+                    if rng.gen_bool(0.3) {
+                        let len: f64 = rng.gen();
+                        adj.set(a, b, len);
+                        adj.set(b, a, len);
+                    }
+                }
+            }
+
+            for dim in 0..6 {
+                let inductive: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, dim);
+                let incremental =
+                    VietorisRips::compute_weighted(Some(VRAlgorithm::Incremental), &adj, dim);
+                let maximal = VietorisRips::compute_weighted(Some(VRAlgorithm::Maximal), &adj, dim);
+
+                assert_eq!(inductive.iter_dim(0).count(), n);
+                assert_eq!(simplices(&inductive), simplices(&incremental));
+                assert_eq!(simplices(&inductive), simplices(&maximal));
+            }
+        }
+    }
+
+    #[test]
+    fn test_algorithms_identical_unweighted() {
+        let mut rng = StdRng::seed_from_u64(31);
+        let mut adj: DenseMatrix<bool> = DenseMatrix::new(40, 40);
+        for a in 0..40 {
+            for b in 0..a {
+                let edge = rng.gen_bool(0.4);
+                adj.set(a, b, edge);
+                adj.set(b, a, edge);
+            }
+        }
+
+        let complexes: Vec<Vec<Simplex>> = [
+            VRAlgorithm::Inductive,
+            VRAlgorithm::Incremental,
+            VRAlgorithm::Maximal,
+        ]
+        .into_iter()
+        .map(|alg| simplices(&VietorisRips::compute(Some(alg), &adj, 4, 1.0)))
+        .collect();
+
+        assert!(complexes[0].iter().any(|s| s.dim() == 3));
+        assert_eq!(complexes[0], complexes[1]);
+        assert_eq!(complexes[0], complexes[2]);
+    }
+
+    #[test]
+    fn test_intersect() {
+        assert_eq!(intersect(&[1, 3, 5, 7], &[0, 3, 4, 7, 9]), vec![3, 7]);
+        assert!(intersect(&[], &[1]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_deterministic() {
+        let mut rng = StdRng::seed_from_u64(37);
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(60, 60);
+        for a in 0..60 {
+            for b in 0..a {
+                if rng.gen_bool(0.2) {
+                    let len: f64 = rng.gen();
+                    adj.set(a, b, len);
+                    adj.set(b, a, len);
+                }
+            }
+        }
+
+        let expected = simplices(&VietorisRips::compute_weighted(None, &adj, 5));
+        for threads in [1, 2, 3, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let complex = pool
+                .install(|| VietorisRips::compute_weighted(Some(VRAlgorithm::Parallel), &adj, 5));
+            assert_eq!(simplices(&complex), expected);
+        }
+    }
+
+    #[test]
+    fn test_storage_equivalence() {
+        use crate::{simplex_trie_arena, simplex_trie_cycle, simplex_vec};
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(20, 20);
+        for a in 0..20 {
+            for b in 0..a {
+                if rng.gen_bool(0.3) {
+                    let len: f64 = rng.gen();
+                    adj.set(a, b, len);
+                    adj.set(b, a, len);
+                }
+            }
+        }
+
+        // Each backend holds the same simplices, though perhaps not in the
+        // same order.
+        let sorted = |mut s: Vec<Simplex>| {
+            s.sort_by(|a, b| {
+                let ids = |x: &Simplex| x.into_iter().map(|v| v.id()).collect::<Vec<usize>>();
+                ids(a).cmp(&ids(b))
+            });
+            s
+        };
+
+        let btree: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 4);
+        let expected = sorted(btree.into_iter().collect());
+        let arena: SimplicialComplex<simplex_trie_arena::SimplexTrie> =
+            VietorisRips::compute_weighted(Some(VRAlgorithm::Incremental), &adj, 4);
+        assert_eq!(sorted(arena.into_iter().collect()), expected);
+        let cycle: SimplicialComplex<simplex_trie_cycle::SimplexTrie> =
+            VietorisRips::compute_weighted(Some(VRAlgorithm::Maximal), &adj, 4);
+        assert_eq!(sorted(cycle.into_iter().collect()), expected);
+        let vec: SimplicialComplex<simplex_vec::SimplexVec> =
+            VietorisRips::compute_weighted(None, &adj, 4);
+        assert_eq!(sorted(vec.into_iter().collect()), expected);
+        assert_eq!(vec.count(), btree.count());
+
+        // Converting between backends changes nothing.
+        let converted: SimplicialComplex<simplex_vec::SimplexVec> = btree.convert();
+        assert_eq!(sorted(converted.into_iter().collect()), expected);
+        assert_eq!(converted.dim(), 4);
+    }
 }
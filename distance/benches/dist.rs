@@ -1,7 +1,8 @@
 // src/distance/benches/dist.rs
 #![feature(test)]
 extern crate test;
-use distance::{Metric, MetricFn};
+use common::{dense::DenseMatrix, Matrix};
+use distance::{point_cloud, Metric, MetricFn};
 use rand::prelude::*;
 use test::Bencher;
 
@@ -24,3 +25,73 @@ fn bench_manhattan(b: &mut Bencher) {
 
     b.iter(|| x.dist(&y, Some(MetricFn::Manhattan)))
 }
+
+#[bench]
+fn bench_euclidean_f64_kernel(b: &mut Bencher) {
+    let mut rng = rand::thread_rng();
+
+    let x: Vec<f64> = (0..1000).map(|_| rng.gen()).collect();
+    let y: Vec<f64> = (0..1000).map(|_| rng.gen()).collect();
+
+    b.iter(|| MetricFn::Euclidean.dist_float(&x, &y))
+}
+
+#[bench]
+fn bench_manhattan_f64_kernel(b: &mut Bencher) {
+    let mut rng = rand::thread_rng();
+
+    let x: Vec<f64> = (0..1000).map(|_| rng.gen()).collect();
+    let y: Vec<f64> = (0..1000).map(|_| rng.gen()).collect();
+
+    b.iter(|| MetricFn::Manhattan.dist_float(&x, &y))
+}
+
+#[bench]
+fn bench_euclidean_f32_kernel(b: &mut Bencher) {
+    let mut rng = rand::thread_rng();
+
+    let x: Vec<f32> = (0..1000).map(|_| rng.gen()).collect();
+    let y: Vec<f32> = (0..1000).map(|_| rng.gen()).collect();
+
+    b.iter(|| MetricFn::Euclidean.dist_float(&x, &y))
+}
+
+#[bench]
+fn bench_manhattan_f32_kernel(b: &mut Bencher) {
+    let mut rng = rand::thread_rng();
+
+    let x: Vec<f32> = (0..1000).map(|_| rng.gen()).collect();
+    let y: Vec<f32> = (0..1000).map(|_| rng.gen()).collect();
+
+    b.iter(|| MetricFn::Manhattan.dist_float(&x, &y))
+}
+
+// Produce a point cloud suitable for testing.
+fn cloud() -> Vec<Vec<f64>> {
+    let mut rng = rand::thread_rng();
+    (0..500)
+        .map(|_| (0..32).map(|_| rng.gen()).collect())
+        .collect()
+}
+
+// The scalar baseline; every entry of a dense matrix via the Metric trait.
+#[bench]
+fn bench_dist_mat_scalar(b: &mut Bencher) {
+    let cloud = cloud();
+    b.iter(|| {
+        let mut dm = DenseMatrix::new(cloud.len(), cloud.len());
+        for (i, x) in cloud.iter().enumerate() {
+            for (j, y) in cloud.iter().enumerate() {
+                dm.set(i, j, x.dist(y, None));
+            }
+        }
+        dm
+    })
+}
+
+// Run with `--features nightly,parallel` to compare the threaded path.
+#[bench]
+fn bench_to_dist_mat(b: &mut Bencher) {
+    let cloud = cloud();
+    b.iter(|| point_cloud::to_dist_mat(&cloud, None))
+}
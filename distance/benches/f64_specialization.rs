@@ -8,7 +8,6 @@ fn euclidean_f64(a: &[f64], b: &[f64]) -> f64 {
     let r: f64 = a
         .iter()
         .zip(b.iter())
-        .into_iter()
         .fold(0.0, |acc, (x, y)| acc + (x - y).powi(2));
     r.sqrt()
 }
@@ -16,7 +15,6 @@ fn euclidean_f64(a: &[f64], b: &[f64]) -> f64 {
 fn manhattan_f64(a: &[f64], b: &[f64]) -> f64 {
     a.iter()
         .zip(b.iter())
-        .into_iter()
         .fold(0.0, |acc, (x, y)| acc + (x - y).abs())
 }
 
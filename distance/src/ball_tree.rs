@@ -0,0 +1,234 @@
+// distance/src/ball_tree.rs
+//! A ball tree over point cloud data.
+//! Pruning relies only upon the triangle inequality, so unlike the k-d tree
+//! this remains valid for any metric regardless of its coordinate structure.
+use crate::{MetricFn, NearestHeap, SpatialIndex};
+use common::float::Float;
+
+/// The maximum number of points stored within a leaf.
+const LEAF_SIZE: usize = 8;
+
+/// A ball containing every point beneath it; a leaf holds its points
+/// directly.
+struct BallNode<F> {
+    center: usize,
+    radius: F,
+    children: Option<(usize, usize)>,
+    points: Vec<usize>,
+}
+
+/// A ball tree backed with an arena, borrowing the cloud it indexes.
+pub struct BallTree<'a, F = f64> {
+    cloud: &'a [Vec<F>],
+    metric: MetricFn,
+    arena: Vec<BallNode<F>>,
+    root: Option<usize>,
+}
+
+impl<'a, F: Float> BallTree<'a, F> {
+    /// Build a ball tree by recursively splitting each ball between its two
+    /// (approximately) furthest points.
+    pub fn new(cloud: &'a [Vec<F>], metric: Option<MetricFn>) -> Self {
+        let mut tree = BallTree {
+            cloud,
+            metric: metric.unwrap_or_default(),
+            arena: Vec::new(),
+            root: None,
+        };
+
+        if !cloud.is_empty() {
+            tree.root = Some(tree.build((0..cloud.len()).collect()));
+        }
+
+        tree
+    }
+
+    fn dist(&self, a: usize, b: usize) -> F {
+        self.metric.dist_float(&self.cloud[a], &self.cloud[b])
+    }
+
+    /// Return the point within 'points' furthest from 'from'.
+    fn furthest(&self, from: usize, points: &[usize]) -> (usize, F) {
+        points
+            .iter()
+            .map(|p| (*p, self.dist(from, *p)))
+            .fold((from, F::ZERO), |a, b| if b.1 > a.1 { b } else { a })
+    }
+
+    fn build(&mut self, points: Vec<usize>) -> usize {
+        let center = points[0];
+        let (a, radius) = self.furthest(center, &points);
+        let (b, _) = self.furthest(a, &points);
+
+        let (near_a, near_b): (Vec<usize>, Vec<usize>) = points
+            .iter()
+            .partition(|p| self.dist(a, **p) <= self.dist(b, **p));
+
+        // Small balls, or those made of duplicate points, become leaves.
+        let leaf = points.len() <= LEAF_SIZE || near_a.is_empty() || near_b.is_empty();
+        let children = match leaf {
+            true => None,
+            false => Some((self.build(near_a), self.build(near_b))),
+        };
+
+        self.arena.push(BallNode {
+            center,
+            radius,
+            children,
+            points: match children {
+                Some(_) => vec![],
+                None => points,
+            },
+        });
+        self.arena.len() - 1
+    }
+
+    /// Return the number of points within the tree.
+    pub fn len(&self) -> usize {
+        self.cloud.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cloud.is_empty()
+    }
+
+    fn _radius(&self, node: usize, query: &[F], eps: F, r: &mut Vec<(usize, F)>) {
+        let node = &self.arena[node];
+
+        // No point within this ball can be closer than this.
+        if self.metric.dist_float(query, &self.cloud[node.center]) - node.radius >= eps {
+            return;
+        }
+
+        match node.children {
+            Some((left, right)) => {
+                self._radius(left, query, eps, r);
+                self._radius(right, query, eps, r);
+            }
+            None => {
+                for p in &node.points {
+                    let d = self.metric.dist_float(query, &self.cloud[*p]);
+                    if d < eps {
+                        r.push((*p, d));
+                    }
+                }
+            }
+        }
+    }
+
+    fn _knn(&self, node: usize, center_dist: F, query: &[F], heap: &mut NearestHeap<F>) {
+        let node = &self.arena[node];
+        if center_dist - node.radius > heap.bound() {
+            return;
+        }
+
+        match node.children {
+            Some((left, right)) => {
+                let left_dist = self
+                    .metric
+                    .dist_float(query, &self.cloud[self.arena[left].center]);
+                let right_dist = self
+                    .metric
+                    .dist_float(query, &self.cloud[self.arena[right].center]);
+
+                // Descend into the closer ball first to tighten the bound.
+                match left_dist <= right_dist {
+                    true => {
+                        self._knn(left, left_dist, query, heap);
+                        self._knn(right, right_dist, query, heap);
+                    }
+                    false => {
+                        self._knn(right, right_dist, query, heap);
+                        self._knn(left, left_dist, query, heap);
+                    }
+                }
+            }
+            None => {
+                for p in &node.points {
+                    heap.push(*p, self.metric.dist_float(query, &self.cloud[*p]));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, F: Float> SpatialIndex<F> for BallTree<'a, F> {
+    fn radius_query(&self, query: &[F], epsilon: F) -> Vec<(usize, F)> {
+        let mut r = Vec::new();
+        if let Some(root) = self.root {
+            self._radius(root, query, epsilon, &mut r);
+        }
+
+        r.sort_by_key(|(i, _)| *i);
+        r
+    }
+
+    fn knn_query(&self, query: &[F], k: usize) -> Vec<(usize, F)> {
+        let mut heap = NearestHeap::new(k);
+        if let (Some(root), true) = (self.root, k > 0) {
+            let d = self
+                .metric
+                .dist_float(query, &self.cloud[self.arena[root].center]);
+            self._knn(root, d, query, &mut heap);
+        }
+
+        heap.into_sorted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn cloud(n: usize, dim: usize) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(29);
+        (0..n)
+            .map(|_| (0..dim).map(|_| rng.gen()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_ball_tree_radius_query() {
+        let cloud = cloud(200, 5);
+        for metric in [MetricFn::Euclidean, MetricFn::Manhattan] {
+            let tree = BallTree::new(&cloud, Some(metric));
+            assert_eq!(tree.len(), 200);
+
+            for query in cloud.iter().take(20) {
+                let expected: Vec<usize> = (0..cloud.len())
+                    .filter(|i| metric.dist_float(query, &cloud[*i]) < 0.6)
+                    .collect();
+                let found: Vec<usize> = tree.radius_query(query, 0.6).iter().map(|x| x.0).collect();
+                assert_eq!(found, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ball_tree_knn_query() {
+        let cloud = cloud(200, 5);
+        for metric in [MetricFn::Euclidean, MetricFn::Manhattan] {
+            let tree = BallTree::new(&cloud, Some(metric));
+
+            for query in cloud.iter().take(20) {
+                let mut expected: Vec<(usize, f64)> = (0..cloud.len())
+                    .map(|i| (i, metric.dist_float(query, &cloud[i])))
+                    .collect();
+                expected.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+                expected.truncate(7);
+
+                assert_eq!(tree.knn_query(query, 7), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ball_tree_duplicates() {
+        // Identical points can never be split; they must still be found.
+        let cloud = vec![vec![1.0, 1.0]; 50];
+        let tree = BallTree::new(&cloud, None);
+        assert_eq!(tree.radius_query(&[1.0, 1.0], 0.1).len(), 50);
+        assert_eq!(tree.knn_query(&[0.0, 0.0], 3).len(), 3);
+    }
+}
@@ -0,0 +1,267 @@
+// distance/src/datasets.rs
+//! Synthetic point clouds sampled from shapes of known topology, for tests
+//! and benchmarks. Each is deterministic given its seed, and carries the
+//! Betti numbers over Z2 of the shape it was sampled from; noise and
+//! outliers perturb the cloud away from them. Requires the 'random' feature.
+use rand::prelude::*;
+use std::f64::consts::{PI, TAU};
+
+/// Enumeration for each shape a point cloud can be sampled from.
+#[derive(Clone, Copy, Debug)]
+pub enum Shape {
+    /// The unit n-sphere within n + 1 dimensions.
+    Sphere(usize),
+    /// A torus within three dimensions; the radius of its tube's centre
+    /// from the origin, and of the tube.
+    Torus(f64, f64),
+    /// A Klein bottle embedded within four dimensions; a tube, as with the
+    /// torus, which turns over once through the fourth dimension as it
+    /// circles the origin. It cannot be embedded within three.
+    KleinBottle(f64, f64),
+    /// A rectangle rolled into a spiral within three dimensions.
+    SwissRoll,
+    /// A lemniscate within the plane; two loops meeting at the origin.
+    FigureEight,
+    /// Two unit circles linked within three dimensions, each passing through
+    /// the centre of the other.
+    LinkedRings,
+    /// A number of discs within the plane, spread along a line.
+    Clusters(usize),
+}
+
+impl Shape {
+    /// Return the Betti numbers over Z2 of the shape, from dimension zero up
+    /// to that of the shape.
+    pub fn betti(&self) -> Vec<usize> {
+        match self {
+            Shape::Sphere(0) => vec![2],
+            Shape::Sphere(n) => {
+                let mut r = vec![0; n + 1];
+                r[0] = 1;
+                r[*n] = 1;
+                r
+            }
+            Shape::Torus(..) | Shape::KleinBottle(..) => vec![1, 2, 1],
+            Shape::SwissRoll => vec![1, 0, 0],
+            Shape::FigureEight => vec![1, 2],
+            Shape::LinkedRings => vec![2, 2],
+            Shape::Clusters(k) => vec![*k],
+        }
+    }
+
+    /// Return a point sampled from the shape; 'i' of 'n' picks the part of
+    /// a shape made of several.
+    fn sample<R: Rng>(&self, rng: &mut R, i: usize, n: usize) -> Vec<f64> {
+        match *self {
+            Shape::Sphere(d) => {
+                // The direction of a Gaussian is uniform over the sphere.
+                let x: Vec<f64> = (0..=d).map(|_| gaussian(rng)).collect();
+                let norm = x.iter().map(|v| v * v).sum::<f64>().sqrt();
+                x.iter().map(|v| v / norm).collect()
+            }
+            Shape::Torus(big, small) => {
+                let (u, v) = (rng.gen_range(0.0..TAU), rng.gen_range(0.0..TAU));
+                let r = big + small * v.cos();
+                vec![r * u.cos(), r * u.sin(), small * v.sin()]
+            }
+            Shape::KleinBottle(big, small) => {
+                let (u, v) = (rng.gen_range(0.0..TAU), rng.gen_range(0.0..TAU));
+                let r = big + small * v.cos();
+                vec![
+                    r * u.cos(),
+                    r * u.sin(),
+                    small * v.sin() * (u / 2.0).cos(),
+                    small * v.sin() * (u / 2.0).sin(),
+                ]
+            }
+            Shape::SwissRoll => {
+                let t = 1.5 * PI * (1.0 + 2.0 * rng.gen::<f64>());
+                vec![t * t.cos(), rng.gen_range(0.0..10.0), t * t.sin()]
+            }
+            Shape::FigureEight => {
+                let t = rng.gen_range(0.0..TAU);
+                vec![t.cos(), t.sin() * t.cos()]
+            }
+            Shape::LinkedRings => {
+                let t = rng.gen_range(0.0..TAU);
+                match i < n / 2 {
+                    true => vec![t.cos(), t.sin(), 0.0],
+                    false => vec![1.0 + t.cos(), 0.0, t.sin()],
+                }
+            }
+            Shape::Clusters(k) => {
+                // Uniform over a disc of radius one half about its centre.
+                let (t, r) = (rng.gen_range(0.0..TAU), 0.5 * rng.gen::<f64>().sqrt());
+                let centre = 3.0 * (i * k / n) as f64;
+                vec![centre + r * t.cos(), r * t.sin()]
+            }
+        }
+    }
+}
+
+/// A point cloud and the Betti numbers of the shape it was sampled from.
+pub struct Dataset {
+    pub points: Vec<Vec<f64>>,
+    pub betti: Vec<usize>,
+}
+
+/// Return a standard normal sample, by the Box-Muller transform.
+fn gaussian<R: Rng>(rng: &mut R) -> f64 {
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (TAU * v).cos()
+}
+
+/// Sample 'n' points from a shape with the given seed. Each coordinate is
+/// perturbed by Gaussian noise with deviation 'noise', if given, and then
+/// 'outliers' points are added uniformly within the bounding box of the
+/// cloud. Panics if a `Shape::Clusters` is given fewer points than
+/// clusters, which would leave one empty, or if outliers are asked of an
+/// empty cloud, which has no bounding box.
+pub fn sample(shape: Shape, n: usize, seed: u64, noise: Option<f64>, outliers: usize) -> Dataset {
+    if let Shape::Clusters(k) = shape {
+        if k == 0 {
+            panic!("the number of clusters must be non-zero!");
+        }
+
+        if n < k {
+            panic!("each cluster must receive at least one point!");
+        }
+    }
+
+    if outliers > 0 && n == 0 {
+        panic!("outliers require a non-empty cloud to bound them!");
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut points: Vec<Vec<f64>> = (0..n).map(|i| shape.sample(&mut rng, i, n)).collect();
+
+    if let Some(sd) = noise {
+        for x in points.iter_mut().flatten() {
+            *x += sd * gaussian(&mut rng);
+        }
+    }
+
+    if outliers > 0 {
+        let d = points[0].len();
+        let lo: Vec<f64> = (0..d)
+            .map(|k| points.iter().map(|p| p[k]).fold(f64::INFINITY, f64::min))
+            .collect();
+        let hi: Vec<f64> = (0..d)
+            .map(|k| {
+                points
+                    .iter()
+                    .map(|p| p[k])
+                    .fold(f64::NEG_INFINITY, f64::max)
+            })
+            .collect();
+
+        for _ in 0..outliers {
+            let p = (0..d)
+                .map(|k| lo[k] + (hi[k] - lo[k]) * rng.gen::<f64>())
+                .collect();
+            points.push(p);
+        }
+    }
+
+    Dataset {
+        points,
+        betti: shape.betti(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn norm(p: &[f64]) -> f64 {
+        p.iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+
+    #[test]
+    fn test_sample_deterministic() {
+        let a = sample(Shape::Torus(2.0, 0.5), 50, 7, Some(0.1), 5);
+        let b = sample(Shape::Torus(2.0, 0.5), 50, 7, Some(0.1), 5);
+        let c = sample(Shape::Torus(2.0, 0.5), 50, 8, Some(0.1), 5);
+        assert_eq!(a.points, b.points);
+        assert_ne!(a.points, c.points);
+        assert_eq!(a.points.len(), 55);
+        assert_eq!(a.betti, vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn test_sample_shapes() {
+        let eps = 1e-9;
+
+        let sphere = sample(Shape::Sphere(3), 100, 0, None, 0);
+        assert!(sphere.points.iter().all(|p| p.len() == 4));
+        assert!(sphere.points.iter().all(|p| (norm(p) - 1.0).abs() < eps));
+        assert_eq!(sphere.betti, vec![1, 0, 0, 1]);
+        assert_eq!(Shape::Sphere(0).betti(), vec![2]);
+
+        // Each point lies at the minor radius from the centre of the tube.
+        let torus = sample(Shape::Torus(2.0, 0.5), 100, 0, None, 0);
+        for p in &torus.points {
+            let r = norm(&p[..2]) - 2.0;
+            assert!((norm(&[r, p[2]]) - 0.5).abs() < eps);
+        }
+
+        let klein = sample(Shape::KleinBottle(2.0, 0.5), 100, 0, None, 0);
+        for p in &klein.points {
+            let r = norm(&p[..2]) - 2.0;
+            assert!((norm(&[r, p[2], p[3]]) - 0.5).abs() < eps);
+        }
+
+        // Half of the points on each ring.
+        let rings = sample(Shape::LinkedRings, 100, 0, None, 0);
+        assert_eq!(rings.points.iter().filter(|p| p[2] == 0.0).count(), 50);
+        assert_eq!(rings.betti, vec![2, 2]);
+
+        let clusters = sample(Shape::Clusters(3), 90, 0, None, 0);
+        for (i, p) in clusters.points.iter().enumerate() {
+            let centre = [3.0 * (i / 30) as f64, 0.0];
+            assert!(norm(&[p[0] - centre[0], p[1] - centre[1]]) <= 0.5);
+        }
+
+        let eight = sample(Shape::FigureEight, 100, 0, None, 0);
+        assert!(eight.points.iter().all(|p| p.len() == 2 && norm(p) <= 1.0));
+        assert_eq!(
+            sample(Shape::SwissRoll, 10, 0, None, 0).betti,
+            vec![1, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_sample_noise_outliers() {
+        let clean = sample(Shape::Sphere(1), 200, 3, None, 0);
+        let noisy = sample(Shape::Sphere(1), 200, 3, Some(0.05), 0);
+        let drift: f64 = noisy.points.iter().map(|p| (norm(p) - 1.0).abs()).sum();
+        assert!(drift > 0.0 && drift / 200.0 < 0.1);
+        assert_eq!(clean.points.len(), noisy.points.len());
+
+        // Outliers lie within the bounding box of the circle.
+        let outliers = sample(Shape::Sphere(1), 200, 3, None, 20);
+        assert_eq!(outliers.points[..200], clean.points[..]);
+        assert!(outliers.points[200..]
+            .iter()
+            .all(|p| p.iter().all(|x| x.abs() <= 1.0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_no_clusters() {
+        sample(Shape::Clusters(0), 10, 0, None, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_empty_clusters() {
+        sample(Shape::Clusters(4), 3, 0, None, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sample_outliers_empty() {
+        sample(Shape::Sphere(1), 0, 0, None, 5);
+    }
+}
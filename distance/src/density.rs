@@ -0,0 +1,195 @@
+// distance/src/density.rs
+//! Density estimates and the distance-to-measure (DTM) over point clouds.
+//! Each yields one value per point, suitable as vertex weights so that
+//! outliers enter a filtration late.
+use crate::{kd_tree::KdTree, neighborhood::knn_lists, MetricFn, SpatialIndex};
+use common::float::Float;
+
+/// Enumeration for each kernel of a kernel density estimate.
+#[derive(Clone, Copy, Default)]
+pub enum Kernel {
+    /// exp(-u^2 / 2).
+    #[default]
+    Gaussian,
+    /// 1 - u^2, for u < 1.
+    Epanechnikov,
+    /// 1, for u < 1.
+    Uniform,
+}
+
+impl Kernel {
+    /// Evaluate the kernel at 'u', a distance scaled by the bandwidth.
+    fn eval<F: Float>(&self, u: F) -> F {
+        let one = F::from_f64(1.0);
+        match self {
+            Kernel::Gaussian => (F::ZERO - u * u / F::from_f64(2.0)).exp(),
+            Kernel::Epanechnikov if u < one => one - u * u,
+            Kernel::Uniform if u < one => one,
+            _ => F::ZERO,
+        }
+    }
+}
+
+/// Compute a kernel density estimate at each point, (1 / n) * sum K(d / h)
+/// over every point of the cloud, including itself. The estimate is left
+/// unnormalized by the volume of the kernel; values are comparable between
+/// points of a single cloud and bandwidth. Kernels with compact support only
+/// visit neighbors within 'bandwidth', found with a k-d tree.
+pub fn kde<F: Float>(
+    cloud: &[Vec<F>],
+    bandwidth: F,
+    kernel: Option<Kernel>,
+    metric: Option<MetricFn>,
+) -> Vec<F> {
+    let kernel = kernel.unwrap_or_default();
+    let metric = metric.unwrap_or_default();
+    let n = F::from_f64(cloud.len() as f64);
+
+    match kernel {
+        Kernel::Gaussian => cloud
+            .iter()
+            .map(|a| {
+                cloud
+                    .iter()
+                    .map(|b| kernel.eval(metric.dist_float(a, b) / bandwidth))
+                    .sum::<F>()
+                    / n
+            })
+            .collect(),
+        _ => {
+            let tree = KdTree::new(cloud, Some(metric));
+            cloud
+                .iter()
+                .map(|a| {
+                    tree.radius_query(a, bandwidth)
+                        .into_iter()
+                        .map(|(_, d)| kernel.eval(d / bandwidth))
+                        .sum::<F>()
+                        / n
+                })
+                .collect()
+        }
+    }
+}
+
+/// Compute the k-nearest-neighbor density estimate at each point,
+/// k / (n * r_k^D), where r_k is the distance to the k'th nearest neighbor
+/// (excluding the point itself) and D the dimension of the cloud. As with
+/// `kde`, the volume of the unit ball is omitted. Points with 'k' duplicates
+/// have infinite density.
+pub fn knn_density<F: Float>(cloud: &[Vec<F>], k: usize, metric: Option<MetricFn>) -> Vec<F> {
+    if k == 0 {
+        panic!("k must be non-zero!");
+    }
+
+    let tree = KdTree::new(cloud, metric);
+    let scale = F::from_f64(k as f64 / cloud.len() as f64);
+
+    knn_lists(&tree, cloud, k)
+        .iter()
+        .zip(cloud)
+        .map(|(nbrs, point)| {
+            let rk = nbrs.last().map_or(F::INFINITY, |(_, d)| *d);
+            match rk > F::ZERO {
+                true => scale / rk.powi(point.len() as i32),
+                false => F::INFINITY,
+            }
+        })
+        .collect()
+}
+
+/// Compute the empirical distance-to-measure at each point with mass k / n;
+/// the root mean square distance to its 'k' nearest points, counting itself.
+/// Unlike the distance to the nearest point, the DTM is stable under the
+/// addition of a few outliers.
+/// Referenced from: 'Geometric Inference for Probability Measures - Frédéric Chazal · David Cohen-Steiner · Quentin Mérigot'
+pub fn dtm<F: Float>(cloud: &[Vec<F>], k: usize, metric: Option<MetricFn>) -> Vec<F> {
+    if k == 0 {
+        panic!("k must be non-zero!");
+    }
+
+    let tree = KdTree::new(cloud, metric);
+    cloud
+        .iter()
+        .map(|point| {
+            let nbrs = tree.knn_query(point, k);
+            let sq: F = nbrs.iter().map(|(_, d)| *d * *d).sum();
+            (sq / F::from_f64(nbrs.len() as f64)).sqrt()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line() -> Vec<Vec<f64>> {
+        vec![vec![0.0], vec![1.0], vec![3.0], vec![7.0]]
+    }
+
+    /// 40 points on the unit circle, then 3 outliers close to its center.
+    fn noisy_circle() -> Vec<Vec<f64>> {
+        let mut cloud: Vec<Vec<f64>> = (0..40)
+            .map(|i| {
+                let t = i as f64 * std::f64::consts::TAU / 40.0;
+                vec![t.cos(), t.sin()]
+            })
+            .collect();
+        cloud.extend([vec![0.0, 0.0], vec![0.1, 0.3], vec![-0.2, -0.1]]);
+        cloud
+    }
+
+    #[test]
+    fn test_kde() {
+        let line = line();
+        let r = kde(&line, 1.0, Some(Kernel::Uniform), None);
+        assert_eq!(r, vec![0.25, 0.25, 0.25, 0.25]);
+
+        let r = kde(&line, 2.0, Some(Kernel::Epanechnikov), None);
+        assert_eq!(r, vec![0.4375, 0.4375, 0.25, 0.25]);
+
+        // Every point is at least as dense under the Gaussian kernel, which
+        // reaches across the whole cloud.
+        let g = kde(&line, 2.0, None, None);
+        assert!(g.iter().zip(&r).all(|(a, b)| a >= b));
+        assert!(g[1] > g[0] && g[0] > g[2] && g[2] > g[3]);
+
+        let cloud = noisy_circle();
+        let r = kde(&cloud, 0.3, None, None);
+        assert!(r[40..].iter().all(|x| *x < r[0]));
+    }
+
+    #[test]
+    fn test_knn_density() {
+        let line = line();
+        let r = knn_density(&line, 1, None);
+        assert_eq!(r, vec![0.25, 0.25, 0.125, 0.0625]);
+
+        let dup = vec![vec![1.0], vec![1.0], vec![2.0]];
+        assert_eq!(knn_density(&dup, 1, None)[0], f64::INFINITY);
+
+        let cloud = noisy_circle();
+        let r = knn_density(&cloud, 5, None);
+        assert!(r[40..].iter().all(|x| *x < r[0]));
+    }
+
+    #[test]
+    fn test_dtm() {
+        let line = line();
+
+        // With k = 1 each point is its own nearest neighbor.
+        assert_eq!(dtm(&line, 1, None), vec![0.0; 4]);
+        let r = dtm(&line, 2, None);
+        assert_eq!(r[0], 0.5f64.sqrt());
+        assert_eq!(r[3], 8.0f64.sqrt());
+
+        let cloud = noisy_circle();
+        let r = dtm(&cloud, 5, None);
+        let max = r[..40].iter().copied().fold(0.0, f64::max);
+        assert!(r[40..].iter().all(|x| *x > max));
+
+        // f32 clouds produce f32 values.
+        let cloud: Vec<Vec<f32>> = vec![vec![0.0], vec![2.0]];
+        assert_eq!(dtm(&cloud, 2, None), vec![2.0f32.sqrt(); 2]);
+    }
+}
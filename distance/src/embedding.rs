@@ -0,0 +1,224 @@
+// distance/src/embedding.rs
+//! Time-delay (sliding window) embeddings of a time series into a point
+//! cloud, and heuristics for choosing the delay.
+//! Referenced from: 'Detecting Strange Attractors in Turbulence - Floris Takens'
+
+/// Enumeration for how each window is normalized once embedded.
+#[derive(Clone, Copy)]
+pub enum Normalization {
+    /// Subtract the mean of the window from each coordinate.
+    Center,
+    /// Center, then scale the window to unit Euclidean norm; a constant
+    /// window is left at the origin.
+    Unit,
+}
+
+/// Embed 'series' as the windows (x[s], x[s + delay], ...) of 'dim'
+/// coordinates, for every start s = 0, stride, 2 * stride, ... that fits.
+/// The result may be handed to `point_cloud::to_dist_mat`. Panics if 'dim',
+/// 'delay' or 'stride' is zero.
+pub fn delay_embedding(
+    series: &[f64],
+    dim: usize,
+    delay: usize,
+    stride: usize,
+    norm: Option<Normalization>,
+) -> Vec<Vec<f64>> {
+    if dim == 0 || delay == 0 || stride == 0 {
+        panic!("dim, delay and stride must be non-zero!");
+    }
+
+    let span = (dim - 1) * delay;
+    if series.len() <= span {
+        return vec![];
+    }
+
+    (0..series.len() - span)
+        .step_by(stride)
+        .map(|s| {
+            let mut window: Vec<f64> = (0..dim).map(|i| series[s + i * delay]).collect();
+            if let Some(norm) = norm {
+                normalize(&mut window, norm);
+            }
+
+            window
+        })
+        .collect()
+}
+
+fn normalize(window: &mut [f64], norm: Normalization) {
+    let mean = window.iter().sum::<f64>() / window.len() as f64;
+    window.iter_mut().for_each(|x| *x -= mean);
+
+    if let Normalization::Unit = norm {
+        let len = window.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if len > 0.0 {
+            window.iter_mut().for_each(|x| *x /= len);
+        }
+    }
+}
+
+/// Return the autocorrelation of 'series' at 'lag'; 1.0 at a lag of zero,
+/// and 0.0 for a constant series or a lag beyond its length.
+pub fn autocorrelation(series: &[f64], lag: usize) -> f64 {
+    if lag >= series.len() {
+        return 0.0;
+    }
+
+    let mean = series.iter().sum::<f64>() / series.len() as f64;
+    let var: f64 = series.iter().map(|x| (x - mean) * (x - mean)).sum();
+    if var == 0.0 {
+        return 0.0;
+    }
+
+    let cov: f64 = series
+        .iter()
+        .zip(&series[lag..])
+        .map(|(a, b)| (a - mean) * (b - mean))
+        .sum();
+    cov / var
+}
+
+/// Choose a delay as the first lag, up to 'max_lag', at which the
+/// autocorrelation is no longer positive; coordinates of each window are
+/// then roughly linearly independent.
+pub fn delay_by_autocorrelation(series: &[f64], max_lag: usize) -> Option<usize> {
+    (1..=max_lag).find(|lag| autocorrelation(series, *lag) <= 0.0)
+}
+
+/// Return the mutual information (in nats) between 'series' and itself
+/// shifted by 'lag', estimated with a histogram of 'bins' equal bins.
+pub fn mutual_information(series: &[f64], lag: usize, bins: usize) -> f64 {
+    if lag >= series.len() || bins == 0 {
+        return 0.0;
+    }
+
+    let min = series.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = series.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let bin = |x: f64| match max > min {
+        true => (((x - min) / (max - min) * bins as f64) as usize).min(bins - 1),
+        false => 0,
+    };
+
+    let n = series.len() - lag;
+    let mut joint = vec![0usize; bins * bins];
+    let (mut a, mut b) = (vec![0usize; bins], vec![0usize; bins]);
+    for (x, y) in series.iter().zip(&series[lag..]) {
+        let (i, j) = (bin(*x), bin(*y));
+        joint[i * bins + j] += 1;
+        a[i] += 1;
+        b[j] += 1;
+    }
+
+    let mut r = 0.0;
+    for i in 0..bins {
+        for j in 0..bins {
+            let p = joint[i * bins + j] as f64 / n as f64;
+            if p > 0.0 {
+                r += p * (p * (n * n) as f64 / (a[i] * b[j]) as f64).ln();
+            }
+        }
+    }
+
+    r
+}
+
+/// Choose a delay as the first local minimum of the mutual information up to
+/// 'max_lag'; coordinates of each window then share the least information.
+/// Referenced from: 'Independent Coordinates for Strange Attractors from Mutual Information - Andrew Fraser · Harry Swinney'
+pub fn delay_by_mutual_information(series: &[f64], max_lag: usize, bins: usize) -> Option<usize> {
+    let mi: Vec<f64> = (0..=max_lag + 1)
+        .map(|lag| mutual_information(series, lag, bins))
+        .collect();
+
+    (1..=max_lag).find(|lag| mi[*lag] < mi[lag - 1] && mi[*lag] <= mi[lag + 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point_cloud::to_dist_mat;
+    use common::Matrix;
+
+    /// A sine wave with a period of 40 samples.
+    fn sine(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|t| (t as f64 * std::f64::consts::TAU / 40.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_delay_embedding() {
+        let series: Vec<f64> = (0..10).map(|x| x as f64).collect();
+
+        let cloud = delay_embedding(&series, 3, 2, 1, None);
+        assert_eq!(cloud.len(), 6);
+        assert_eq!(cloud[0], vec![0.0, 2.0, 4.0]);
+        assert_eq!(cloud[5], vec![5.0, 7.0, 9.0]);
+
+        let cloud = delay_embedding(&series, 3, 2, 4, None);
+        assert_eq!(cloud, vec![vec![0.0, 2.0, 4.0], vec![4.0, 6.0, 8.0]]);
+
+        // Too short for a single window.
+        assert!(delay_embedding(&series, 4, 4, 1, None).is_empty());
+        assert_eq!(delay_embedding(&series, 1, 1, 1, None).len(), 10);
+    }
+
+    #[test]
+    fn test_delay_embedding_normalization() {
+        let series: Vec<f64> = (0..10).map(|x| x as f64).collect();
+
+        // Every window of a line is identical once centered.
+        let cloud = delay_embedding(&series, 3, 2, 1, Some(Normalization::Center));
+        assert!(cloud.iter().all(|w| *w == vec![-2.0, 0.0, 2.0]));
+
+        let cloud = delay_embedding(&series, 2, 1, 1, Some(Normalization::Unit));
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        for w in &cloud {
+            assert!((w[0] + h).abs() < 1e-12 && (w[1] - h).abs() < 1e-12);
+        }
+
+        let constant = delay_embedding(&[1.0; 4], 2, 1, 1, Some(Normalization::Unit));
+        assert!(constant.iter().all(|w| *w == vec![0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_sine_embedding_is_a_circle() {
+        // With a quarter period delay, (sin t, cos t) lies on the unit circle.
+        let cloud = delay_embedding(&sine(200), 2, 10, 1, None);
+        for w in &cloud {
+            assert!((w[0] * w[0] + w[1] * w[1] - 1.0).abs() < 1e-9);
+        }
+
+        let dm = to_dist_mat(&cloud, None);
+        assert_eq!(dm.dim(), (cloud.len(), cloud.len()));
+    }
+
+    #[test]
+    fn test_autocorrelation() {
+        let series = sine(400);
+        assert_eq!(autocorrelation(&series, 0), 1.0);
+        assert!(autocorrelation(&series, 20) < -0.9);
+        assert_eq!(autocorrelation(&[1.0; 5], 1), 0.0);
+        assert_eq!(autocorrelation(&series, 400), 0.0);
+
+        let tau = delay_by_autocorrelation(&series, 40).unwrap();
+        assert!((10..=11).contains(&tau));
+        assert_eq!(delay_by_autocorrelation(&series, 5), None);
+    }
+
+    #[test]
+    fn test_mutual_information() {
+        // A period of roughly 40 samples, which never repeats exactly.
+        let series: Vec<f64> = (0..2000).map(|t| (t as f64 * 0.157).sin()).collect();
+
+        // A series shares the most information with itself.
+        let mi0 = mutual_information(&series, 0, 16);
+        assert!((1..40).all(|lag| mutual_information(&series, lag, 16) <= mi0));
+        assert_eq!(mutual_information(&[1.0; 10], 1, 16), 0.0);
+
+        // The estimate is noisy about a quarter period.
+        let tau = delay_by_mutual_information(&series, 40, 16).unwrap();
+        assert!((8..=12).contains(&tau));
+    }
+}
@@ -0,0 +1,199 @@
+// distance/src/kd_tree.rs
+//! A k-d tree over point cloud data.
+//! Every coordinate difference is a lower bound on both the Euclidean and
+//! Manhattan distance, so a single splitting plane suffices to prune either.
+use crate::{MetricFn, NearestHeap, SpatialIndex};
+use common::float::Float;
+
+/// A single point within the tree; the axis it splits on and its children.
+struct KdNode {
+    point: usize,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// A k-d tree backed with an arena, borrowing the cloud it indexes.
+pub struct KdTree<'a, F = f64> {
+    cloud: &'a [Vec<F>],
+    metric: MetricFn,
+    arena: Vec<KdNode>,
+    root: Option<usize>,
+}
+
+/// Fetch a coordinate; missing coordinates are treated as zero.
+fn coord<F: Float>(point: &[F], axis: usize) -> F {
+    point.get(axis).copied().unwrap_or_default()
+}
+
+impl<'a, F: Float> KdTree<'a, F> {
+    /// Build a balanced k-d tree by splitting on the median of each axis in
+    /// turn.
+    pub fn new(cloud: &'a [Vec<F>], metric: Option<MetricFn>) -> Self {
+        let mut tree = KdTree {
+            cloud,
+            metric: metric.unwrap_or_default(),
+            arena: Vec::with_capacity(cloud.len()),
+            root: None,
+        };
+
+        let mut indices: Vec<usize> = (0..cloud.len()).collect();
+        tree.root = tree.build(&mut indices, 0);
+        tree
+    }
+
+    fn build(&mut self, indices: &mut [usize], depth: usize) -> Option<usize> {
+        let first = *indices.first()?;
+        let axis = depth % self.cloud[first].len().max(1);
+
+        let cloud = self.cloud;
+        let median = indices.len() / 2;
+        indices.select_nth_unstable_by(median, |a, b| {
+            coord(&cloud[*a], axis).total_cmp(&coord(&cloud[*b], axis))
+        });
+
+        let point = indices[median];
+        let (lower, upper) = indices.split_at_mut(median);
+        let left = self.build(lower, depth + 1);
+        let right = self.build(&mut upper[1..], depth + 1);
+
+        self.arena.push(KdNode {
+            point,
+            axis,
+            left,
+            right,
+        });
+        Some(self.arena.len() - 1)
+    }
+
+    /// Return the number of points within the tree.
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    fn _radius(&self, node: Option<usize>, query: &[F], eps: F, r: &mut Vec<(usize, F)>) {
+        if let Some(node) = node.map(|n| &self.arena[n]) {
+            let point = &self.cloud[node.point];
+            let d = self.metric.dist_float(query, point);
+            if d < eps {
+                r.push((node.point, d));
+            }
+
+            let diff = coord(query, node.axis) - coord(point, node.axis);
+            let (near, far) = match diff < F::ZERO {
+                true => (node.left, node.right),
+                false => (node.right, node.left),
+            };
+
+            self._radius(near, query, eps, r);
+            // Every point across the plane is at least 'diff' away.
+            if diff.abs() < eps {
+                self._radius(far, query, eps, r);
+            }
+        }
+    }
+
+    fn _knn(&self, node: Option<usize>, query: &[F], heap: &mut NearestHeap<F>) {
+        if let Some(node) = node.map(|n| &self.arena[n]) {
+            let point = &self.cloud[node.point];
+            heap.push(node.point, self.metric.dist_float(query, point));
+
+            let diff = coord(query, node.axis) - coord(point, node.axis);
+            let (near, far) = match diff < F::ZERO {
+                true => (node.left, node.right),
+                false => (node.right, node.left),
+            };
+
+            self._knn(near, query, heap);
+            if diff.abs() <= heap.bound() {
+                self._knn(far, query, heap);
+            }
+        }
+    }
+}
+
+impl<'a, F: Float> SpatialIndex<F> for KdTree<'a, F> {
+    fn radius_query(&self, query: &[F], epsilon: F) -> Vec<(usize, F)> {
+        let mut r = Vec::new();
+        self._radius(self.root, query, epsilon, &mut r);
+        r.sort_by_key(|(i, _)| *i);
+        r
+    }
+
+    fn knn_query(&self, query: &[F], k: usize) -> Vec<(usize, F)> {
+        if k == 0 {
+            return vec![];
+        }
+
+        let mut heap = NearestHeap::new(k);
+        self._knn(self.root, query, &mut heap);
+        heap.into_sorted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn cloud(n: usize, dim: usize) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(17);
+        (0..n)
+            .map(|_| (0..dim).map(|_| rng.gen()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_kd_tree_radius_query() {
+        let cloud = cloud(200, 3);
+        for metric in [MetricFn::Euclidean, MetricFn::Manhattan] {
+            let tree = KdTree::new(&cloud, Some(metric));
+            assert_eq!(tree.len(), 200);
+
+            for query in cloud.iter().take(20) {
+                let expected: Vec<usize> = (0..cloud.len())
+                    .filter(|i| metric.dist_float(query, &cloud[*i]) < 0.3)
+                    .collect();
+                let found: Vec<usize> = tree.radius_query(query, 0.3).iter().map(|x| x.0).collect();
+                assert_eq!(found, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kd_tree_knn_query() {
+        let cloud = cloud(200, 3);
+        for metric in [MetricFn::Euclidean, MetricFn::Manhattan] {
+            let tree = KdTree::new(&cloud, Some(metric));
+
+            for query in cloud.iter().take(20) {
+                let mut expected: Vec<(usize, f64)> = (0..cloud.len())
+                    .map(|i| (i, metric.dist_float(query, &cloud[i])))
+                    .collect();
+                expected.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+                expected.truncate(7);
+
+                assert_eq!(tree.knn_query(query, 7), expected);
+            }
+        }
+
+        // The query point is its own nearest neighbor.
+        let tree = KdTree::new(&cloud, None);
+        assert_eq!(tree.knn_query(&cloud[5], 1), vec![(5, 0.0)]);
+        assert!(tree.knn_query(&cloud[5], 0).is_empty());
+        assert_eq!(tree.knn_query(&cloud[5], 500).len(), 200);
+    }
+
+    #[test]
+    fn test_kd_tree_empty() {
+        let cloud: Vec<Vec<f64>> = vec![];
+        let tree = KdTree::new(&cloud, None);
+        assert!(tree.is_empty());
+        assert!(tree.radius_query(&[0.0], 1.0).is_empty());
+        assert!(tree.knn_query(&[0.0], 1).is_empty());
+    }
+}
@@ -1,20 +1,48 @@
 // distance/src/lib.rs
 //! Utilities for computing distance.
+pub mod ball_tree;
+#[cfg(feature = "random")]
+pub mod datasets;
+pub mod density;
 mod dist;
+pub mod embedding;
+pub mod kd_tree;
 pub mod neighborhood;
 pub mod point_cloud;
+pub mod subsample;
+pub mod tiled;
+use common::float::Float;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::ops::{Mul, Sub};
 
 /// Enumeration for each specific distance formula.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub enum MetricFn {
+    #[default]
     Euclidean,
     Manhattan,
 }
 
-impl Default for MetricFn {
-    fn default() -> Self {
-        MetricFn::Euclidean
+impl MetricFn {
+    /// Compute the distance between two slices with this formula.
+    pub fn dist<T>(&self, a: &[T], b: &[T]) -> f64
+    where
+        T: Sub<Output = T> + Mul<Output = T> + Into<f64> + Copy,
+    {
+        match self {
+            MetricFn::Euclidean => dist::euclidean_dist(a, b),
+            MetricFn::Manhattan => dist::manhattan_dist(a, b),
+        }
+    }
+
+    /// Compute the distance between two slices of floats with a vectorized
+    /// kernel; the result retains the precision of the input.
+    pub fn dist_float<F: Float>(&self, a: &[F], b: &[F]) -> F {
+        match self {
+            MetricFn::Euclidean => dist::euclidean_lanes(a, b),
+            MetricFn::Manhattan => dist::manhattan_lanes(a, b),
+        }
     }
 }
 
@@ -35,19 +63,83 @@ where
     type Output = f64;
 
     fn dist(&self, rhs: &Vec<T>, metric_fn: Option<MetricFn>) -> Self::Output {
-        let metric_fn = metric_fn.unwrap_or_default();
-        match metric_fn {
-            MetricFn::Euclidean => dist::euclidean_dist(self, rhs),
-            MetricFn::Manhattan => dist::manhattan_dist(self, rhs),
+        metric_fn.unwrap_or_default().dist(self, rhs)
+    }
+}
+
+/// A spatial index over a point cloud, answering neighborhood queries without
+/// producing the NxN distance matrix.
+pub trait SpatialIndex<F: Float = f64> {
+    /// Return every point strictly within 'epsilon' of 'query' as
+    /// (index, distance), ordered by index.
+    fn radius_query(&self, query: &[F], epsilon: F) -> Vec<(usize, F)>;
+
+    /// Return the 'k' nearest points to 'query' as (index, distance), ordered
+    /// by distance; ties are broken by index.
+    fn knn_query(&self, query: &[F], k: usize) -> Vec<(usize, F)>;
+}
+
+/// A (distance, index) pair ordered by distance, then index.
+#[derive(PartialEq)]
+struct Candidate<F>(F, usize);
+
+impl<F: Float> Ord for Candidate<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.0.total_cmp(&other.0) {
+            Ordering::Equal => self.1.cmp(&other.1),
+            r => r,
         }
     }
 }
 
+impl<F: Float> PartialOrd for Candidate<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Eq for Candidate<F> {}
+
+/// A bounded max-heap retaining the 'k' closest candidates seen so far.
+pub(crate) struct NearestHeap<F>(usize, BinaryHeap<Candidate<F>>);
+
+impl<F: Float> NearestHeap<F> {
+    pub(crate) fn new(k: usize) -> Self {
+        NearestHeap(k, BinaryHeap::with_capacity(k + 1))
+    }
+
+    /// Offer a point to the heap, evicting the furthest if over capacity.
+    pub(crate) fn push(&mut self, index: usize, dist: F) {
+        self.1.push(Candidate(dist, index));
+        if self.1.len() > self.0 {
+            self.1.pop();
+        }
+    }
+
+    /// The distance a point must not exceed to enter the heap.
+    pub(crate) fn bound(&self) -> F {
+        match (self.1.len() < self.0, self.1.peek()) {
+            (false, Some(c)) => c.0,
+            _ => F::INFINITY,
+        }
+    }
+
+    /// Consume the heap, returning (index, distance) ordered by distance.
+    pub(crate) fn into_sorted(self) -> Vec<(usize, F)> {
+        self.1
+            .into_sorted_vec()
+            .into_iter()
+            .map(|c| (c.1, c.0))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_dist_impl() {
         let a = vec![1, 0];
         assert_eq!(a.dist(&a, Some(MetricFn::Euclidean)), 0.0);
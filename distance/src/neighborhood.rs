@@ -1,9 +1,28 @@
 // src/complex/src/neighborhood.rs
 //! Utilities for computing neighborhood graphs.
-use common::{sparse::SparseMatrix, Matrix};
+use crate::{kd_tree::KdTree, MetricFn, SpatialIndex};
+use common::{float::Float, sparse::SparseMatrix, Matrix};
+
+/// Enumeration for how k-nearest-neighbor relations become edges.
+#[derive(Clone, Copy, Default)]
+pub enum KnnGraph {
+    /// An edge (i, j) for each j amongst the k nearest neighbors of i; the
+    /// matrix is not symmetric. A Vietoris-Rips complex reads only the
+    /// entries below the diagonal, dropping each edge (i, j) with i < j,
+    /// so build one from the Symmetric or Mutual graph instead.
+    Directed,
+    /// i ~ j if either is amongst the k nearest neighbors of the other.
+    #[default]
+    Symmetric,
+    /// i ~ j if both are amongst the k nearest neighbors of the other.
+    Mutual,
+}
 
 /// Compute an adjacency matrix from a distance matrix and an epsilon value.
-pub fn to_adjacency<M: Matrix<f64>>(dist: &M, epsilon: f64) -> SparseMatrix<bool> {
+/// Comparisons happen in the precision of the matrix; with f32 distances,
+/// pairs which differ from 'epsilon' only beyond f32 precision may be
+/// classified differently than with f64.
+pub fn to_adjacency<F: Float, M: Matrix<F>>(dist: &M, epsilon: F) -> SparseMatrix<bool> {
     let (rows, cols) = dist.dim();
     let mut sm = SparseMatrix::new(rows, cols);
 
@@ -20,10 +39,154 @@ pub fn to_adjacency<M: Matrix<f64>>(dist: &M, epsilon: f64) -> SparseMatrix<bool
     sm
 }
 
+/// Compute a weighted adjacency matrix from a distance matrix and an epsilon
+/// value; each edge retains its length.
+pub fn to_weighted_adjacency<F: Float, M: Matrix<F>>(dist: &M, epsilon: F) -> SparseMatrix<F> {
+    let (rows, cols) = dist.dim();
+    let mut sm = SparseMatrix::new(rows, cols);
+
+    for c in 0..cols {
+        for r in 0..rows {
+            if let Some(val) = dist.get(r, c) {
+                if *val < epsilon {
+                    sm.set(r, c, *val);
+                }
+            }
+        }
+    }
+
+    sm
+}
+
+/// Compute a weighted adjacency matrix directly from a point cloud and an
+/// epsilon value; see `cloud_to_adjacency`.
+pub fn cloud_to_weighted_adjacency<F: Float>(
+    cloud: &[Vec<F>],
+    epsilon: F,
+    metric: Option<MetricFn>,
+) -> SparseMatrix<F> {
+    let tree = KdTree::new(cloud, metric);
+    let mut sm = SparseMatrix::new(cloud.len(), cloud.len());
+
+    for (r, point) in cloud.iter().enumerate() {
+        for (c, d) in tree.radius_query(point, epsilon) {
+            sm.set(r, c, d);
+        }
+    }
+
+    sm
+}
+
+/// Compute an adjacency matrix directly from a point cloud and an epsilon
+/// value; neighbors are located with a k-d tree rather than a NxN distance
+/// matrix.
+pub fn cloud_to_adjacency<F: Float>(
+    cloud: &[Vec<F>],
+    epsilon: F,
+    metric: Option<MetricFn>,
+) -> SparseMatrix<bool> {
+    let tree = KdTree::new(cloud, metric);
+    let mut sm = SparseMatrix::new(cloud.len(), cloud.len());
+
+    for (r, point) in cloud.iter().enumerate() {
+        for (c, _) in tree.radius_query(point, epsilon) {
+            sm.set(r, c, true);
+        }
+    }
+
+    sm
+}
+
+/// Return the 'k' nearest neighbors of each point, excluding itself.
+pub(crate) fn knn_lists<F: Float, S: SpatialIndex<F>>(
+    index: &S,
+    cloud: &[Vec<F>],
+    k: usize,
+) -> Vec<Vec<(usize, F)>> {
+    cloud
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let mut nbrs = index.knn_query(point, k + 1);
+            nbrs.retain(|(j, _)| *j != i);
+            nbrs.truncate(k);
+            nbrs
+        })
+        .collect()
+}
+
+/// Compute a k-nearest-neighbor adjacency matrix from a point cloud. Unlike
+/// an epsilon threshold, every point receives (at least) 'k' neighbors
+/// regardless of the local sampling density. A point is never its own
+/// neighbor. Each kind but Directed is symmetric, and can be passed to
+/// `VietorisRips::compute` as is.
+pub fn to_knn_adjacency<F: Float>(
+    cloud: &[Vec<F>],
+    k: usize,
+    kind: Option<KnnGraph>,
+    metric: Option<MetricFn>,
+) -> SparseMatrix<bool> {
+    let tree = KdTree::new(cloud, metric);
+    let nbrs = knn_lists(&tree, cloud, k);
+    let mut sm = SparseMatrix::new(cloud.len(), cloud.len());
+
+    for (i, list) in nbrs.iter().enumerate() {
+        for (j, _) in list {
+            match kind.unwrap_or_default() {
+                KnnGraph::Directed => sm.set(i, *j, true),
+                KnnGraph::Symmetric => {
+                    sm.set(i, *j, true);
+                    sm.set(*j, i, true);
+                }
+                KnnGraph::Mutual => {
+                    if nbrs[*j].iter().any(|(x, _)| *x == i) {
+                        sm.set(i, *j, true);
+                    }
+                }
+            }
+        }
+    }
+
+    sm
+}
+
+/// Compute a continuous k-nearest-neighbor (CkNN) adjacency matrix from a
+/// point cloud; i ~ j when d(i, j) < delta * sqrt(d_k(i) * d_k(j)), where
+/// d_k is the distance to the k'th nearest neighbor. Referenced from:
+/// 'Consistent Manifold Representation for Topological Data Analysis -
+/// Tyrus Berry · Timothy Sauer'.
+pub fn to_cknn_adjacency<F: Float>(
+    cloud: &[Vec<F>],
+    k: usize,
+    delta: F,
+    metric: Option<MetricFn>,
+) -> SparseMatrix<bool> {
+    let tree = KdTree::new(cloud, metric);
+    let dk: Vec<F> = knn_lists(&tree, cloud, k)
+        .iter()
+        .map(|nbrs| nbrs.last().map_or(F::ZERO, |(_, d)| *d))
+        .collect();
+    let max_dk = dk.iter().copied().fold(F::ZERO, F::max);
+
+    let mut sm = SparseMatrix::new(cloud.len(), cloud.len());
+    for (i, point) in cloud.iter().enumerate() {
+        // No neighbor can lie further away than the largest scaled radius.
+        for (j, d) in tree.radius_query(point, delta * (dk[i] * max_dk).sqrt()) {
+            if i != j && d < delta * (dk[i] * dk[j]).sqrt() {
+                sm.set(i, j, true);
+            }
+        }
+    }
+
+    sm
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::point_cloud::to_dist_mat;
     use common::dense::DenseMatrix;
+    use rand::prelude::*;
 
     #[test]
     fn test_to_adjacency() {
@@ -47,4 +210,124 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_cloud_to_adjacency() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let cloud: Vec<Vec<f64>> = (0..100)
+            .map(|_| (0..2).map(|_| rng.gen()).collect())
+            .collect();
+
+        // Should agree with the adjacency matrix of the full distance matrix.
+        for metric in [MetricFn::Euclidean, MetricFn::Manhattan] {
+            let expected = to_adjacency(&to_dist_mat(&cloud, Some(metric)), 0.2);
+            let adj = cloud_to_adjacency(&cloud, 0.2, Some(metric));
+            assert_eq!(adj.dim(), (100, 100));
+
+            for c in 0..100 {
+                for r in 0..100 {
+                    assert_eq!(adj.get(r, c), expected.get(r, c));
+                }
+            }
+        }
+    }
+
+    // Points on a line at 0, 1, 3, 7.
+    fn line() -> Vec<Vec<f64>> {
+        vec![vec![0.0], vec![1.0], vec![3.0], vec![7.0]]
+    }
+
+    fn edges(adj: &SparseMatrix<bool>) -> Vec<(usize, usize)> {
+        let (rows, cols) = adj.dim();
+        let mut r = vec![];
+        for i in 0..rows {
+            for j in 0..cols {
+                if let Some(true) = adj.get(i, j) {
+                    r.push((i, j));
+                }
+            }
+        }
+
+        r
+    }
+
+    #[test]
+    fn test_to_knn_adjacency() {
+        let cloud = line();
+
+        // Nearest neighbors: 0 -> 1, 1 -> 0, 2 -> 1, 3 -> 2.
+        let adj = to_knn_adjacency(&cloud, 1, Some(KnnGraph::Directed), None);
+        assert_eq!(edges(&adj), vec![(0, 1), (1, 0), (2, 1), (3, 2)]);
+
+        let adj = to_knn_adjacency(&cloud, 1, None, None);
+        assert_eq!(
+            edges(&adj),
+            vec![(0, 1), (1, 0), (1, 2), (2, 1), (2, 3), (3, 2)]
+        );
+
+        let adj = to_knn_adjacency(&cloud, 1, Some(KnnGraph::Mutual), None);
+        assert_eq!(edges(&adj), vec![(0, 1), (1, 0)]);
+
+        // Each point has k neighbors at the very least.
+        let adj = to_knn_adjacency(&cloud, 2, Some(KnnGraph::Directed), None);
+        assert_eq!(edges(&adj).len(), 8);
+    }
+
+    #[test]
+    fn test_to_cknn_adjacency() {
+        let cloud = line();
+
+        // d_k = 1, 1, 2, 4; only consecutive points are within
+        // 1.5 * sqrt(d_k(i) * d_k(j)) of one another.
+        let adj = to_cknn_adjacency(&cloud, 1, 1.5, None);
+        assert_eq!(
+            edges(&adj),
+            vec![(0, 1), (1, 0), (1, 2), (2, 1), (2, 3), (3, 2)]
+        );
+
+        // The comparison is strict, matching `to_adjacency`.
+        let adj = to_cknn_adjacency(&cloud, 1, 1.0, None);
+        assert!(edges(&adj).is_empty());
+    }
+
+    #[test]
+    fn test_weighted_adjacency() {
+        let cloud = line();
+        let expected = [(0, 0, 0.0), (0, 1, 1.0), (1, 1, 0.0), (1, 2, 2.0)];
+
+        let adj = to_weighted_adjacency(&to_dist_mat(&cloud, None), 2.5);
+        let adj_cloud = cloud_to_weighted_adjacency(&cloud, 2.5, None);
+        for (r, c, len) in expected {
+            assert_eq!(adj.get(r, c), Some(&len));
+            assert_eq!(adj.get(c, r), Some(&len));
+            assert_eq!(adj_cloud.get(r, c), Some(&len));
+            assert_eq!(adj_cloud.get(c, r), Some(&len));
+        }
+
+        // 2 -> 3 is too long to be an edge; the diagonal is retained.
+        assert_eq!(adj.get(2, 3), None);
+        assert_eq!(adj_cloud.get(2, 3), None);
+        assert_eq!(adj.iter().count(), 8);
+        assert_eq!(adj_cloud.iter().count(), 8);
+    }
+
+    #[test]
+    fn test_f32_adjacency() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let cloud: Vec<Vec<f32>> = (0..100)
+            .map(|_| (0..2).map(|_| rng.gen()).collect())
+            .collect();
+
+        // f32 data should stay f32 throughout; the edges should agree.
+        let expected = to_weighted_adjacency(&to_dist_mat(&cloud, None), 0.2f32);
+        let adj: SparseMatrix<f32> = cloud_to_weighted_adjacency(&cloud, 0.2f32, None);
+        for c in 0..100 {
+            for r in 0..100 {
+                assert_eq!(adj.get(r, c), expected.get(r, c));
+            }
+        }
+
+        let knn = to_knn_adjacency(&cloud, 3, Some(KnnGraph::Directed), None);
+        assert_eq!(knn.iter().count(), 300);
+    }
 }
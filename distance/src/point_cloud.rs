@@ -1,18 +1,37 @@
 // src/distance/src/point_cloud.rs
-use crate::{Metric, MetricFn};
+use crate::MetricFn;
 /// Utilities for producing distance matrices from point cloud data.
-use common::{dense::DenseMatrix, Matrix};
+use common::{condensed::CondensedMatrix, float::Float, Matrix};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-/// Produce a NxN distance matrix.
-pub fn to_dist_mat(cloud: &[Vec<f64>], metric: Option<MetricFn>) -> DenseMatrix<f64> {
-    let mut dm = DenseMatrix::new(cloud.len(), cloud.len());
+/// The minimum number of rows handed to a single thread.
+#[cfg(feature = "parallel")]
+const ROW_BLOCK: usize = 16;
 
-    for (i, a) in cloud.iter().enumerate() {
-        for (j, b) in cloud.iter().enumerate() {
-            let d = a.dist(b, metric);
-            dm.set(i, j, d);
+/// Produce a NxN distance matrix. A metric is symmetric with a zero diagonal,
+/// so only the N * (N - 1) / 2 distances with i < j are computed and stored.
+/// With the `parallel` feature, blocks of rows are computed across threads.
+/// Distances are computed and stored in the precision of the cloud.
+pub fn to_dist_mat<F: Float>(cloud: &[Vec<F>], metric: Option<MetricFn>) -> CondensedMatrix<F> {
+    let metric = metric.unwrap_or_default();
+    let mut dm = CondensedMatrix::new(cloud.len(), cloud.len());
+
+    let fill = |(i, row): (usize, &mut [F])| {
+        for (k, d) in row.iter_mut().enumerate() {
+            *d = metric.dist_float(&cloud[i], &cloud[i + k + 1]);
         }
-    }
+    };
+
+    #[cfg(feature = "parallel")]
+    dm.rows_mut()
+        .into_par_iter()
+        .enumerate()
+        .with_min_len(ROW_BLOCK)
+        .for_each(fill);
+
+    #[cfg(not(feature = "parallel"))]
+    dm.rows_mut().into_iter().enumerate().for_each(fill);
 
     dm
 }
@@ -49,4 +68,23 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_to_dist_mat_matches_metric() {
+        use crate::Metric;
+
+        let cloud: Vec<Vec<f64>> = (0..50)
+            .map(|i| (0..13).map(|x| ((x * i) % 7) as f64 / 3.0).collect())
+            .collect();
+
+        for metric in [MetricFn::Euclidean, MetricFn::Manhattan] {
+            let mat = to_dist_mat(&cloud, Some(metric));
+            for (i, a) in cloud.iter().enumerate() {
+                for (j, b) in cloud.iter().enumerate() {
+                    let d = a.dist(b, Some(metric));
+                    assert!((mat.get(i, j).unwrap() - d).abs() < 1e-9);
+                }
+            }
+        }
+    }
 }
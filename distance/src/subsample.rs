@@ -0,0 +1,249 @@
+// distance/src/subsample.rs
+//! Landmark selection for reducing a point cloud before computing topology.
+//! Each method reports its covering radius r, the furthest any point lies
+//! from its nearest landmark; the persistence diagram of the subsample is
+//! then within 2r of the original in bottleneck distance.
+//! Referenced from: 'Stability of Persistence Diagrams - Cohen-Steiner · Edelsbrunner · Harer'
+use crate::MetricFn;
+use common::{float::Float, Matrix};
+#[cfg(feature = "random")]
+use rand::prelude::*;
+
+/// A source of pairwise distances between 'len' points, implemented for both
+/// point clouds and precomputed distance matrices.
+pub trait Distances {
+    /// Return the number of points.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the distance between points 'i' and 'j'.
+    fn dist(&self, i: usize, j: usize) -> f64;
+}
+
+impl<M: Matrix<f64>> Distances for M {
+    fn len(&self) -> usize {
+        self.dim().0
+    }
+
+    /// Missing entries are treated as infinitely far apart.
+    fn dist(&self, i: usize, j: usize) -> f64 {
+        self.get(i, j).copied().unwrap_or(f64::INFINITY)
+    }
+}
+
+/// A point cloud paired with the metric to measure it under.
+pub struct Cloud<'a, F> {
+    points: &'a [Vec<F>],
+    metric: MetricFn,
+}
+
+impl<'a, F: Float> Cloud<'a, F> {
+    pub fn new(points: &'a [Vec<F>], metric: Option<MetricFn>) -> Self {
+        Cloud {
+            points,
+            metric: metric.unwrap_or_default(),
+        }
+    }
+}
+
+impl<'a, F: Float> Distances for Cloud<'a, F> {
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn dist(&self, i: usize, j: usize) -> f64 {
+        self.metric
+            .dist_float(&self.points[i], &self.points[j])
+            .into()
+    }
+}
+
+/// The selected landmarks, in order of selection, and their covering radius.
+#[derive(Debug, PartialEq)]
+pub struct Subsample {
+    pub indices: Vec<usize>,
+    pub radius: f64,
+}
+
+/// Greedily select landmarks, each the furthest point from those already
+/// chosen, until 'stop' holds for (landmarks, covering radius).
+fn max_min<D, S>(d: &D, start: usize, stop: S) -> Subsample
+where
+    D: Distances,
+    S: Fn(usize, f64) -> bool,
+{
+    let mut indices = vec![];
+    // The distance from each point to its nearest landmark.
+    let mut nearest = vec![f64::INFINITY; d.len()];
+    let mut next = start;
+
+    while !stop(indices.len(), nearest[next]) {
+        indices.push(next);
+        for (i, n) in nearest.iter_mut().enumerate() {
+            *n = n.min(d.dist(next, i));
+        }
+
+        // The furthest point, ties broken by index.
+        next = (0..nearest.len())
+            .max_by(|a, b| nearest[*a].total_cmp(&nearest[*b]).then(b.cmp(a)))
+            .unwrap();
+    }
+
+    Subsample {
+        indices,
+        radius: nearest[next],
+    }
+}
+
+/// Farthest-point (max-min) sampling of 'k' landmarks, beginning at 'start'
+/// or the first point. The covering radius is within a factor of two of the
+/// best achievable with 'k' landmarks.
+pub fn farthest_point<D: Distances>(d: &D, k: usize, start: Option<usize>) -> Subsample {
+    if d.is_empty() {
+        return Subsample {
+            indices: vec![],
+            radius: 0.0,
+        };
+    }
+
+    max_min(d, start.unwrap_or(0), |count, radius| {
+        count >= k || radius == 0.0
+    })
+}
+
+/// Select 'k' landmarks uniformly at random without replacement; the same
+/// 'seed' always yields the same landmarks, ordered by index. Requires the
+/// 'random' feature.
+#[cfg(feature = "random")]
+pub fn random<D: Distances>(d: &D, k: usize, seed: u64) -> Subsample {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices = rand::seq::index::sample(&mut rng, d.len(), k.min(d.len())).into_vec();
+    indices.sort_unstable();
+
+    Subsample {
+        radius: covering_radius(d, &indices),
+        indices,
+    }
+}
+
+/// Construct an ε-net: landmarks pairwise at least 'epsilon' apart, such
+/// that every point lies strictly within 'epsilon' of a landmark.
+pub fn epsilon_net<D: Distances>(d: &D, epsilon: f64) -> Subsample {
+    if d.is_empty() {
+        return Subsample {
+            indices: vec![],
+            radius: 0.0,
+        };
+    }
+
+    max_min(d, 0, |_, radius| radius < epsilon)
+}
+
+/// Return the furthest any point lies from its nearest landmark; infinite if
+/// there are points but no landmarks.
+pub fn covering_radius<D: Distances>(d: &D, landmarks: &[usize]) -> f64 {
+    (0..d.len())
+        .map(|i| {
+            landmarks
+                .iter()
+                .map(|l| d.dist(*l, i))
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point_cloud::to_dist_mat;
+    use common::condensed::CondensedMatrix;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn line() -> Vec<Vec<f64>> {
+        vec![vec![0.0], vec![1.0], vec![3.0], vec![7.0]]
+    }
+
+    fn cloud(n: usize) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(5);
+        (0..n)
+            .map(|_| (0..2).map(|_| rng.gen()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_farthest_point() {
+        let line = line();
+        let d = Cloud::new(&line, None);
+
+        let s = farthest_point(&d, 2, None);
+        assert_eq!(s.indices, vec![0, 3]);
+        assert_eq!(s.radius, 3.0);
+
+        let s = farthest_point(&d, 3, Some(2));
+        assert_eq!(s.indices, vec![2, 3, 0]);
+        assert_eq!(s.radius, 1.0);
+
+        // Every point selected; nothing left uncovered.
+        let s = farthest_point(&d, 10, None);
+        assert_eq!(s.indices.len(), 4);
+        assert_eq!(s.radius, 0.0);
+
+        let s = farthest_point(&d, 0, None);
+        assert!(s.indices.is_empty());
+        assert_eq!(s.radius, f64::INFINITY);
+    }
+
+    #[test]
+    fn test_precomputed() {
+        let cloud = cloud(100);
+        let dm: CondensedMatrix<f64> = to_dist_mat(&cloud, None);
+        let d = Cloud::new(&cloud, None);
+
+        assert_eq!(farthest_point(&dm, 10, None), farthest_point(&d, 10, None));
+        #[cfg(feature = "random")]
+        assert_eq!(random(&dm, 10, 3), random(&d, 10, 3));
+        assert_eq!(epsilon_net(&dm, 0.2), epsilon_net(&d, 0.2));
+    }
+
+    #[test]
+    #[cfg(feature = "random")]
+    fn test_random() {
+        let cloud = cloud(100);
+        let d = Cloud::new(&cloud, None);
+
+        let s = random(&d, 10, 42);
+        assert_eq!(s, random(&d, 10, 42));
+        assert_eq!(s.indices.len(), 10);
+        assert!(s.indices.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(s.radius, covering_radius(&d, &s.indices));
+
+        // Farthest-point sampling should cover at least as well.
+        assert!(farthest_point(&d, 10, None).radius <= s.radius);
+        assert_eq!(random(&d, 500, 42).indices.len(), 100);
+    }
+
+    #[test]
+    fn test_epsilon_net() {
+        let cloud = cloud(200);
+        let d = Cloud::new(&cloud, None);
+
+        for eps in [0.05, 0.1, 0.3] {
+            let s = epsilon_net(&d, eps);
+            assert!(s.radius < eps);
+            assert_eq!(s.radius, covering_radius(&d, &s.indices));
+            for a in &s.indices {
+                for b in s.indices.iter().filter(|b| *b != a) {
+                    assert!(d.dist(*a, *b) >= eps);
+                }
+            }
+        }
+
+        let empty: Vec<Vec<f64>> = vec![];
+        assert!(epsilon_net(&Cloud::new(&empty, None), 0.1)
+            .indices
+            .is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+// distance/src/tiled.rs
+//! Blocked computation of distance matrices too large to hold in memory.
+//! The upper triangle of the NxN matrix is visited one square tile at a
+//! time, so only a single tile is resident at once.
+use crate::MetricFn;
+use common::{edge_list::EdgeList, float::Float, Matrix};
+
+#[cfg(feature = "mmap")]
+use common::condensed::condensed_index;
+#[cfg(feature = "mmap")]
+use memmap2::MmapMut;
+#[cfg(feature = "mmap")]
+use std::{fs::OpenOptions, io, path::Path};
+
+/// A block of the distance matrix; rows 'row'..'row + rows' and columns
+/// 'col'..'col + cols', stored row-major.
+pub struct Tile<'a, F> {
+    pub row: usize,
+    pub col: usize,
+    pub rows: usize,
+    pub cols: usize,
+    values: &'a [F],
+}
+
+impl<'a, F: Float> Tile<'a, F> {
+    /// Return d(i, j) for global indices 'i' and 'j' within the tile.
+    pub fn get(&self, i: usize, j: usize) -> F {
+        self.values[(i - self.row) * self.cols + (j - self.col)]
+    }
+
+    /// Iterate over the entries (i, j, d) with i < j; tiles on the diagonal
+    /// also hold the lower triangle, which is skipped.
+    pub fn upper(&self) -> impl Iterator<Item = (usize, usize, F)> + '_ {
+        (0..self.rows).flat_map(move |r| {
+            (0..self.cols)
+                .map(move |c| (self.row + r, self.col + c))
+                .filter(|(i, j)| i < j)
+                .map(move |(i, j)| (i, j, self.get(i, j)))
+        })
+    }
+}
+
+/// A tiled distance engine over a point cloud.
+pub struct TiledDistance<'a, F> {
+    cloud: &'a [Vec<F>],
+    metric: MetricFn,
+    tile: usize,
+}
+
+impl<'a, F: Float> TiledDistance<'a, F> {
+    /// Tiles are 'tile' x 'tile' distances; panics if 'tile' is zero.
+    pub fn new(cloud: &'a [Vec<F>], metric: Option<MetricFn>, tile: usize) -> Self {
+        if tile == 0 {
+            panic!("tile size must be non-zero!");
+        }
+
+        TiledDistance {
+            cloud,
+            metric: metric.unwrap_or_default(),
+            tile,
+        }
+    }
+
+    /// Compute each tile of the upper triangle in turn, handing it to
+    /// 'consumer'. The tile buffer is reused, so the consumer must copy out
+    /// anything it wishes to keep.
+    pub fn for_each_tile<C: FnMut(&Tile<F>)>(&self, mut consumer: C) {
+        let n = self.cloud.len();
+        let mut buf = Vec::with_capacity(self.tile * self.tile);
+
+        for row in (0..n).step_by(self.tile) {
+            for col in (row..n).step_by(self.tile) {
+                let rows = self.tile.min(n - row);
+                let cols = self.tile.min(n - col);
+
+                buf.clear();
+                for a in &self.cloud[row..row + rows] {
+                    for b in &self.cloud[col..col + cols] {
+                        buf.push(self.metric.dist_float(a, b));
+                    }
+                }
+
+                consumer(&Tile {
+                    row,
+                    col,
+                    rows,
+                    cols,
+                    values: &buf,
+                });
+            }
+        }
+    }
+
+    /// Stream the matrix, keeping only the edges strictly shorter than
+    /// 'epsilon'. The result may be consumed as a weighted adjacency matrix
+    /// by the neighborhood and Vietoris-Rips utilities.
+    pub fn edges_below(&self, epsilon: F) -> EdgeList<F> {
+        let mut edges = EdgeList::new(self.cloud.len(), self.cloud.len());
+        self.for_each_tile(|tile| {
+            for (i, j, d) in tile.upper() {
+                if d < epsilon {
+                    edges.set(i, j, d);
+                }
+            }
+        });
+
+        edges
+    }
+
+    /// Stream the matrix into a memory-mapped file at 'path' as a condensed
+    /// vector of little-endian values (SciPy's `pdist` order), letting the
+    /// operating system page the matrix out to disk. Requires the 'mmap'
+    /// feature.
+    #[cfg(feature = "mmap")]
+    pub fn write_condensed<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let n = self.cloud.len();
+        let len = n * n.saturating_sub(1) / 2 * F::BYTES;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len as u64)?;
+
+        // Nothing to map.
+        if len == 0 {
+            return Ok(());
+        }
+
+        // The mapping is only valid while no other process truncates the
+        // file; we created and truncated it above, and hold it until done.
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        self.for_each_tile(|tile| {
+            for (i, j, d) in tile.upper() {
+                let offset = condensed_index(n, i, j) * F::BYTES;
+                d.write_le(&mut mmap[offset..offset + F::BYTES]);
+            }
+        });
+
+        mmap.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::neighborhood::to_adjacency;
+    use crate::point_cloud::to_dist_mat;
+    use common::condensed::condensed_index;
+    use rand::prelude::*;
+
+    fn cloud(n: usize) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(11);
+        (0..n)
+            .map(|_| (0..3).map(|_| rng.gen()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_for_each_tile() {
+        let cloud = cloud(23);
+        let dm = to_dist_mat(&cloud, None);
+
+        // Every pair i < j should be visited exactly once.
+        let mut seen = vec![0; 23 * 22 / 2];
+        TiledDistance::new(&cloud, None, 5).for_each_tile(|tile| {
+            assert!(tile.rows <= 5 && tile.cols <= 5);
+            for (i, j, d) in tile.upper() {
+                seen[condensed_index(23, i, j)] += 1;
+                assert_eq!(Some(&d), dm.get(i, j));
+            }
+        });
+
+        assert!(seen.iter().all(|x| *x == 1));
+    }
+
+    #[test]
+    fn test_edges_below() {
+        let cloud = cloud(40);
+        let expected = to_adjacency(&to_dist_mat(&cloud, None), 0.4);
+        let edges = TiledDistance::new(&cloud, None, 7).edges_below(0.4);
+
+        // The edge list is consumable as an adjacency matrix; the diagonal
+        // is never an edge.
+        let adj = to_adjacency(&edges, 0.4);
+        for i in 0..40 {
+            for j in 0..40 {
+                match i == j {
+                    true => assert_eq!(adj.get(i, j), None),
+                    false => assert_eq!(adj.get(i, j), expected.get(i, j)),
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn test_write_condensed() {
+        let cloud: Vec<Vec<f32>> = cloud(30)
+            .iter()
+            .map(|p| p.iter().map(|x| *x as f32).collect())
+            .collect();
+        // Named for the process, as concurrent test runs share the directory.
+        let name = format!("tda_test_write_condensed_{}.bin", std::process::id());
+        let path = std::env::temp_dir().join(name);
+
+        TiledDistance::new(&cloud, None, 4)
+            .write_condensed(&path)
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let values: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        assert_eq!(values, to_dist_mat(&cloud, None).as_slice());
+    }
+}
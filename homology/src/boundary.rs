@@ -12,18 +12,18 @@ pub fn boundary(complex: &SimplicialComplex, p: usize) -> DenseMatrix<usize> {
         panic!("Shouldn't be called for 0'th betti number.")
     }
 
-    // TODO: Count the number of simplices of each dimension during the
-    // creation of the trie or offer up a function that quickly computes
-    // the number of values at a certain depth.
-    let rows = complex.iter_dim(p-1).count();
-    let cols = complex.iter_dim(p).count();
-
-    // Rows are the number of p-1 simplices; cols p-simplices.
+    // Rows are the number of p-1 simplices; cols p-simplices, each placed
+    // at its index within its dimension.
+    let rows = complex.count_dim(p - 1);
+    let cols = complex.count_dim(p);
     let mut mat = DenseMatrix::<usize>::new(rows, cols);
 
-    for (j, bigger) in complex.iter_dim(p).enumerate() {
-        for (i, smaller) in complex.iter_dim(p-1).enumerate() {
-            if smaller.is_face(&bigger) {
+    for bigger in complex.iter_dim(p) {
+        let ids: Vec<usize> = bigger.into_iter().map(|v| v.id()).collect();
+        let j = complex.index(&ids).unwrap();
+        for k in 0..ids.len() {
+            let face: Vec<usize> = [&ids[..k], &ids[k + 1..]].concat();
+            if let Some(i) = complex.index(&face) {
                 mat.set(i, j, 1);
             }
         }
@@ -35,9 +35,77 @@ pub fn boundary(complex: &SimplicialComplex, p: usize) -> DenseMatrix<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use complex::triangulations::simplex;
+    use complex::vietoris_rips::VietorisRips;
+    use complex::ComplexBuilder;
 
     #[test]
     fn test_boundary() {
-        panic!()
+        // The filled triangle on the vertices 0, 1, 2.
+        let complex = simplex(2).complex;
+
+        // Each edge has its two vertices as faces.
+        let mat = boundary(&complex, 1);
+        assert_eq!(mat.dim(), (3, 3));
+        for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+            let j = complex.index(&[a, b]).unwrap();
+            for i in 0..3 {
+                let expected = (i == a || i == b) as usize;
+                assert_eq!(mat.get(complex.index(&[i]).unwrap(), j), Some(&expected));
+            }
+        }
+
+        // Each edge is a face of the single 2-simplex.
+        let mat = boundary(&complex, 2);
+        assert_eq!(mat.dim(), (3, 1));
+        for i in 0..3 {
+            assert_eq!(mat.get(i, 0), Some(&1));
+        }
+    }
+
+    #[test]
+    fn test_boundary_faces() {
+        // The complete complex on 6 vertices; an entry is set exactly when
+        // its row is a face of its column.
+        let mut adj = DenseMatrix::<bool>::new(6, 6);
+        for i in 0..6 {
+            for j in 0..6 {
+                adj.set(i, j, i != j);
+            }
+        }
+        let complex = VietorisRips::compute(None, &adj, 4, 0.0);
+        let index = |s: &complex::Simplex| {
+            let ids: Vec<usize> = s.into_iter().map(|v| v.id()).collect();
+            complex.index(&ids).unwrap()
+        };
+
+        for p in 1..4 {
+            let mat = boundary(&complex, p);
+            assert_eq!(mat.dim(), (complex.count_dim(p - 1), complex.count_dim(p)));
+            for bigger in complex.iter_dim(p) {
+                for smaller in complex.iter_dim(p - 1) {
+                    let expected = smaller.is_face(&bigger) as usize;
+                    assert_eq!(mat.get(index(&smaller), index(&bigger)), Some(&expected));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_boundary_sphere() {
+        // The hollow tetrahedron; each of its triangles has three edges, and
+        // each edge lies on two triangles.
+        let complex: SimplicialComplex = ComplexBuilder::new()
+            .maximal([[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]])
+            .unwrap()
+            .build();
+        let mat = boundary(&complex, 2);
+        assert_eq!(mat.dim(), (6, 4));
+        for j in 0..4 {
+            assert_eq!((0..6).filter(|i| mat.get(*i, j) == Some(&1)).count(), 3);
+        }
+        for i in 0..6 {
+            assert_eq!((0..4).filter(|j| mat.get(i, *j) == Some(&1)).count(), 2);
+        }
     }
 }
@@ -1,50 +1,146 @@
 // homology/src/lib.rs
 //! Computing homology of a SimplicialComplex.
 mod boundary;
+pub mod persistence;
 mod reduction;
 
 use common::Matrix;
 
-/// Compute the 'p'th betti number of a SimplicialComplex.
+/// Return the rank of the 'p'th boundary matrix mod 2; the boundary of a
+/// vertex is empty.
+fn rank_z2(complex: &complex::SimplicialComplex, p: usize) -> usize {
+    if p == 0 {
+        return 0;
+    }
+
+    let mut mat = boundary::boundary(complex, p);
+    reduction::reduce_z2(&mut mat, 0);
+
+    // The reduced matrix is diagonal; its rank the odd entries.
+    let (rows, cols) = mat.dim();
+    (0..rows.min(cols))
+        .filter(|i| mat.get(*i, *i).is_some_and(|v| v % 2 == 1))
+        .count()
+}
+
+/// Compute the 'p'th betti number of a SimplicialComplex over Z2; the rank
+/// of the cycles Zp less that of the boundaries Bp.
 pub fn compute_homology(complex: &complex::SimplicialComplex, p: usize) -> usize {
+    // Zp: the kernel of the p'th boundary.
+    let rank_z = complex.count_dim(p) - rank_z2(complex, p);
 
-    // TODO: Handle the 0'th betti number case, which is an edge case.
+    // Bp: the image of the p+1'th boundary.
+    let rank_b = match complex.count_dim(p + 1) {
+        0 => 0,
+        _ => rank_z2(complex, p + 1),
+    };
 
-    // Zp
-    let mut zmat = boundary::boundary(&complex, p);
-    reduction::reduce_z2(&mut zmat, 0);
+    rank_z - rank_b
+}
 
-    // Bp
-    let mut bmat = boundary::boundary(&complex, p+1);
-    reduction::reduce_z2(&mut bmat, 0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use complex::triangulations::*;
+    use persistence::persistence;
 
-    let (_, z_cols) = zmat.dim();
-    let (b_rows, _) = bmat.dim();
+    #[test]
+    fn test_triangulation_homology() {
+        for t in [
+            simplex(3),
+            sphere(1),
+            sphere(2),
+            sphere(3),
+            torus(),
+            klein_bottle(),
+            projective_plane(),
+            mobius_strip(),
+            wedge(4),
+            dunce_hat(),
+        ] {
+            let betti: Vec<usize> = (0..t.betti.len())
+                .map(|p| compute_homology(&t.complex, p))
+                .collect();
+            assert_eq!(betti, t.betti);
 
-    let mut rank_z: usize = 0;
-    let mut rank_b: usize = 0;
+            // Every simplex enters at once; only the classes which never die
+            // remain in the diagram.
+            let diagram = persistence(&t.complex).unwrap();
+            let betti: Vec<usize> = (0..t.betti.len())
+                .map(|p| diagram.iter().filter(|pair| pair.dim == p).count())
+                .collect();
+            assert_eq!(betti, t.betti);
 
-    // Zp: the number of zero columns.
-    for i in 0..z_cols {
-        if let Some(v) = zmat.get(i, i) {
-            match v % 2 {
-                0 => rank_z += 1,
-                1 => (),
-                _ => unreachable!(),
-            }
+            let euler: i64 = betti
+                .iter()
+                .enumerate()
+                .map(|(p, b)| match p % 2 {
+                    0 => *b as i64,
+                    _ => -(*b as i64),
+                })
+                .sum();
+            assert_eq!(euler, t.complex.euler());
         }
     }
 
-    // Bp: the number of non-zero columns.
-    for i in 0..b_rows {
-        if let Some(v) = zmat.get(i, i) {
-            match v % 2 {
-                0 => (),
-                1 => rank_b += 1,
-                _ => unreachable!(),
+    /// Compute every betti number of a SimplicialComplex.
+    fn betti(complex: &complex::SimplicialComplex) -> Vec<usize> {
+        (0..complex.dim())
+            .map(|p| compute_homology(complex, p))
+            .collect()
+    }
+
+    /// Return the reduced betti numbers; one fewer class in dimension zero.
+    fn reduced(betti: &[usize]) -> Vec<usize> {
+        let mut r = betti.to_vec();
+        r[0] -= 1;
+        r
+    }
+
+    #[test]
+    fn test_operations_homology() {
+        let spaces = [sphere(0), sphere(1), wedge(2), projective_plane()];
+        for x in &spaces {
+            // A cone is contractible.
+            let mut contractible = vec![0; x.betti.len() + 1];
+            contractible[0] = 1;
+            assert_eq!(betti(&x.complex.cone()), contractible);
+
+            // Suspension shifts the reduced homology up a dimension.
+            let suspended = reduced(&betti(&x.complex.suspension()));
+            assert_eq!(suspended[1..], reduced(&x.betti)[..]);
+            assert_eq!(suspended[0], 0);
+
+            // The projective plane is only ever the first factor, to keep the
+            // boundary matrices small.
+            for y in &spaces[..3] {
+                // Kunneth; over a field the homology of the product is the
+                // tensor product of the homology of each.
+                let mut kunneth = vec![0; x.betti.len() + y.betti.len() - 1];
+                for (i, a) in x.betti.iter().enumerate() {
+                    for (j, b) in y.betti.iter().enumerate() {
+                        kunneth[i + j] += a * b;
+                    }
+                }
+                assert_eq!(betti(&x.complex.product(&y.complex)), kunneth);
+
+                let union: Vec<usize> = betti(&x.complex.disjoint_union(&y.complex));
+                let width = x.betti.len().max(y.betti.len());
+                let expected: Vec<usize> = (0..width)
+                    .map(|p| x.betti.get(p).unwrap_or(&0) + y.betti.get(p).unwrap_or(&0))
+                    .collect();
+                assert_eq!(union, expected);
+
+                // The reduced homology of a join; that of each, shifted up a
+                // dimension and multiplied.
+                let mut join = vec![0; x.betti.len() + y.betti.len()];
+                for (i, a) in reduced(&x.betti).iter().enumerate() {
+                    for (j, b) in reduced(&y.betti).iter().enumerate() {
+                        join[i + j + 1] += a * b;
+                    }
+                }
+                assert_eq!(reduced(&betti(&x.complex.join(&y.complex))), join);
             }
         }
     }
-
-    rank_z - rank_b
 }
@@ -0,0 +1,400 @@
+// homology/src/persistence.rs
+//! Computing the persistence diagram of a filtered SimplicialComplex.
+//! Referenced "Computational Topology An Introduction" by Edelsbrunner and
+//! Harer. ISBN 978-0-8218-4925-5
+use complex::morse::GradientField;
+use complex::{ComplexError, SimplicialComplex, Storage};
+use std::collections::HashMap;
+
+/// A point of a persistence diagram; a class of dimension 'dim' born at
+/// 'birth' which dies at 'death', infinite if it never dies.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Pair {
+    pub dim: usize,
+    pub birth: f64,
+    pub death: f64,
+}
+
+/// Return the symmetric difference of two columns, each ordered by index.
+fn add_z2(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut r = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        match (a.get(i), b.get(j)) {
+            (Some(x), Some(y)) if x == y => {
+                i += 1;
+                j += 1;
+            }
+            (Some(x), Some(y)) if x < y => {
+                r.push(*x);
+                i += 1;
+            }
+            (Some(x), None) => {
+                r.push(*x);
+                i += 1;
+            }
+            (_, Some(y)) => {
+                r.push(*y);
+                j += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    r
+}
+
+/// Compute the persistence diagram of a SimplicialComplex, filtered by the
+/// weights of its simplices, by the standard reduction of the boundary
+/// matrix mod 2 (pg. 153). Pairs which are born and die at the same value
+/// are omitted; the remainder are ordered by dimension, birth then death.
+/// Classes of the top dimension never die, as the complex holds no
+/// simplices above it. The complex must be closed under faces, which a
+/// Storage filled through `add_simplex` need not be; otherwise the first
+/// missing face is returned as a `ComplexError::MissingFace`.
+pub fn persistence<S: Storage>(complex: &SimplicialComplex<S>) -> Result<Vec<Pair>, ComplexError> {
+    // Each simplex as its ids and weight, in filtration order.
+    let mut simplices: Vec<(Vec<usize>, f64)> = complex
+        .into_iter()
+        .map(|s| {
+            let mut ids: Vec<usize> = (&s).into_iter().map(|v| v.id()).collect();
+            ids.sort_unstable();
+            (ids, s.weight())
+        })
+        .collect();
+    simplices.sort_by(|x, y| {
+        x.1.total_cmp(&y.1)
+            .then(x.0.len().cmp(&y.0.len()))
+            .then(x.0.cmp(&y.0))
+    });
+
+    let index: HashMap<&[usize], usize> = simplices
+        .iter()
+        .enumerate()
+        .map(|(i, (ids, _))| (ids.as_slice(), i))
+        .collect();
+
+    let cells: Vec<(usize, f64)> = simplices
+        .iter()
+        .map(|(ids, weight)| (ids.len() - 1, *weight))
+        .collect();
+    let columns: Vec<Vec<usize>> = simplices
+        .iter()
+        .map(|(ids, _)| match ids.len() {
+            1 => Ok(vec![]),
+            _ => {
+                let mut col: Vec<usize> = (0..ids.len())
+                    .map(|k| {
+                        let face: Vec<usize> = [&ids[..k], &ids[k + 1..]].concat();
+                        match index.get(face.as_slice()) {
+                            Some(i) => Ok(*i),
+                            None => Err(ComplexError::MissingFace {
+                                simplex: complex.locate(ids).unwrap(),
+                                face,
+                            }),
+                        }
+                    })
+                    .collect::<Result<_, _>>()?;
+                col.sort_unstable();
+                Ok(col)
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(reduce(&cells, columns))
+}
+
+/// Compute the persistence diagram of the Morse complex of a gradient
+/// vector field, spanned by its critical simplices alone; the diagram of
+/// the complex the field was computed over.
+pub fn morse_persistence(field: &GradientField) -> Vec<Pair> {
+    let cells: Vec<(usize, f64)> = field
+        .critical()
+        .iter()
+        .map(|s| (s.dim(), s.weight()))
+        .collect();
+
+    reduce(&cells, field.morse_boundary())
+}
+
+/// Reduce the boundary columns of a filtered chain complex, cells given by
+/// their dimension and weight in filtration order, into a diagram.
+fn reduce(cells: &[(usize, f64)], columns: Vec<Vec<usize>>) -> Vec<Pair> {
+    // The column with each lowest index, and whether a cell is paired.
+    let mut low: HashMap<usize, usize> = HashMap::new();
+    let mut reduced: Vec<Vec<usize>> = Vec::with_capacity(cells.len());
+    let mut paired = vec![false; cells.len()];
+    let mut r = vec![];
+
+    for (j, mut col) in columns.into_iter().enumerate() {
+        while let Some(k) = col.last().and_then(|l| low.get(l)) {
+            col = add_z2(&col, &reduced[*k]);
+        }
+
+        if let Some(l) = col.last() {
+            low.insert(*l, j);
+            paired[*l] = true;
+            paired[j] = true;
+            r.push(Pair {
+                dim: cells[*l].0,
+                birth: cells[*l].1,
+                death: cells[j].1,
+            });
+        }
+        reduced.push(col);
+    }
+
+    for (i, (dim, weight)) in cells.iter().enumerate() {
+        if !paired[i] {
+            r.push(Pair {
+                dim: *dim,
+                birth: *weight,
+                death: f64::INFINITY,
+            });
+        }
+    }
+
+    r.retain(|p| p.birth != p.death);
+    r.sort_by(|a, b| {
+        a.dim
+            .cmp(&b.dim)
+            .then(a.birth.total_cmp(&b.birth))
+            .then(a.death.total_cmp(&b.death))
+    });
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{sparse::SparseMatrix, Matrix};
+    use complex::collapse::{edge_collapse, strong_collapse};
+    use complex::sparse_rips::SparseRips;
+    use complex::vietoris_rips::VietorisRips;
+    use distance::point_cloud::to_dist_mat;
+
+    /// Parse a CSV of points, one per line.
+    fn read(csv: &str) -> Vec<Vec<f64>> {
+        csv.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.split(',').map(|x| x.trim().parse().unwrap()).collect())
+            .collect()
+    }
+
+    /// The ratio between two values, one if they are equal, even if zero or
+    /// infinite.
+    fn ratio(a: f64, b: f64) -> f64 {
+        match a == b {
+            true => 1.0,
+            false => a.max(b) / a.min(b),
+        }
+    }
+
+    /// Whether two diagrams are within the factor 'c' of each other on a log
+    /// scale; their pairs may be matched, within 'c' of each other in both
+    /// birth and death, or left unmatched if within 'c' of the diagonal.
+    fn within_factor(a: &[Pair], b: &[Pair], c: f64) -> bool {
+        // Each pair, then the diagonal projection of each pair of the other.
+        let near = |p: &Pair| (p.death / p.birth).sqrt() <= c;
+        let (n, m) = (a.len(), b.len());
+        let edges: Vec<Vec<usize>> = (0..n + m)
+            .map(|i| match i < n {
+                true => (0..m)
+                    .filter(|j| {
+                        let (x, y) = (&a[i], &b[*j]);
+                        x.dim == y.dim && ratio(x.birth, y.birth).max(ratio(x.death, y.death)) <= c
+                    })
+                    .chain(std::iter::once(m + i).filter(|_| near(&a[i])))
+                    .collect(),
+                false => (0..m)
+                    .filter(|j| *j == i - n && near(&b[*j]))
+                    .chain(m..m + n)
+                    .collect(),
+            })
+            .collect();
+
+        // A perfect matching, by augmenting paths.
+        fn augment(
+            i: usize,
+            edges: &[Vec<usize>],
+            seen: &mut [bool],
+            to: &mut [Option<usize>],
+        ) -> bool {
+            for j in &edges[i] {
+                if !seen[*j] {
+                    seen[*j] = true;
+                    if to[*j].is_none_or(|k| augment(k, edges, seen, to)) {
+                        to[*j] = Some(i);
+                        return true;
+                    }
+                }
+            }
+            false
+        }
+
+        let mut to = vec![None; n + m];
+        (0..n + m).all(|i| augment(i, &edges, &mut vec![false; n + m], &mut to))
+    }
+
+    #[test]
+    fn test_add_z2() {
+        assert_eq!(add_z2(&[0, 2, 5], &[2, 3]), vec![0, 3, 5]);
+        assert!(add_z2(&[1, 4], &[1, 4]).is_empty());
+    }
+
+    #[test]
+    fn test_persistence() {
+        // A square whose diagonal enters later, killing the loop.
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(4, 4);
+        for (a, b, len) in [
+            (1, 0, 1.0),
+            (2, 1, 1.0),
+            (3, 2, 1.0),
+            (3, 0, 1.0),
+            (2, 0, 2.0),
+        ] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 3);
+        let diagram = persistence(&complex).unwrap();
+        let pair = |dim, birth, death| Pair { dim, birth, death };
+        assert_eq!(
+            diagram,
+            vec![
+                pair(0, 0.0, 1.0),
+                pair(0, 0.0, 1.0),
+                pair(0, 0.0, 1.0),
+                pair(0, 0.0, f64::INFINITY),
+                pair(1, 1.0, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapse_preserves_diagram() {
+        for csv in [
+            include_str!("../../examples/circles.csv"),
+            include_str!("../../examples/noisy_circles.csv"),
+        ] {
+            let cloud = read(csv);
+            let dm = to_dist_mat(&cloud, None);
+
+            // Edges up to a radius; every clique fits beneath the dimension,
+            // so the flag filtration is complete in every degree.
+            let n = cloud.len();
+            let mut adj: SparseMatrix<f64> = SparseMatrix::new(n, n);
+            for a in 0..n {
+                for b in 0..n {
+                    match dm.get(a, b) {
+                        Some(len) if a != b && *len <= 1.2 => adj.set(a, b, *len),
+                        _ => (),
+                    }
+                }
+            }
+
+            let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, n + 1);
+            let diagram = persistence(&complex).unwrap();
+
+            let edges: SimplicialComplex =
+                VietorisRips::compute_weighted(None, &edge_collapse(&adj), n + 1);
+            assert!(edges.iter_dim(1).count() < complex.iter_dim(1).count());
+            assert_eq!(persistence(&edges).unwrap(), diagram);
+
+            let strong = strong_collapse(&complex);
+            assert!(strong.into_iter().count() < complex.into_iter().count());
+            assert_eq!(persistence(&strong).unwrap(), diagram);
+        }
+    }
+
+    #[test]
+    fn test_sparse_rips_persistence() {
+        for csv in [
+            include_str!("../../examples/circles.csv"),
+            include_str!("../../examples/noisy_circles.csv"),
+        ] {
+            let dm = to_dist_mat(&read(csv), None);
+            let full: SimplicialComplex = VietorisRips::compute_weighted(None, &dm, 4);
+            // Classes of the top dimension never die, so are left out.
+            let below =
+                |d: Vec<Pair>| -> Vec<Pair> { d.into_iter().filter(|p| p.dim < 3).collect() };
+            let diagram = below(persistence(&full).unwrap());
+
+            for epsilon in [0.1, 0.25, 0.5, 0.9] {
+                let sparse: SimplicialComplex = SparseRips::compute(&dm, epsilon, 4);
+                let approx = below(persistence(&sparse).unwrap());
+                if epsilon >= 0.5 {
+                    assert!(sparse.iter_dim(1).count() < full.iter_dim(1).count());
+                }
+                assert!(
+                    within_factor(&diagram, &approx, 1.0 + epsilon),
+                    "{} {:?} {:?}",
+                    epsilon,
+                    diagram,
+                    approx
+                );
+            }
+        }
+
+        // A diagram is within a factor of itself, but not of a shifted one.
+        let pair = |dim, birth, death| Pair { dim, birth, death };
+        let a = [pair(0, 0.0, f64::INFINITY), pair(1, 1.0, 4.0)];
+        let b = [pair(0, 0.0, f64::INFINITY), pair(1, 1.2, 4.8)];
+        assert!(within_factor(&a, &a, 1.0));
+        assert!(within_factor(&a, &b, 1.2));
+        assert!(!within_factor(&a, &b, 1.1));
+        assert!(within_factor(&a[..1], &b, 2.0));
+        assert!(!within_factor(&a[..1], &b, 1.5));
+    }
+
+    #[test]
+    fn test_morse_persistence() {
+        for csv in [
+            include_str!("../../examples/circles.csv"),
+            include_str!("../../examples/noisy_circles.csv"),
+        ] {
+            let dm = to_dist_mat(&read(csv), None);
+            let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &dm, 4);
+            let field = GradientField::new(&complex);
+
+            assert!(field.critical().len() < complex.into_iter().count());
+            assert_eq!(morse_persistence(&field), persistence(&complex).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_dataset_persistence() {
+        use distance::datasets::{sample, Shape};
+
+        // The long-lived classes of each dimension recover the shape; short
+        // ones are left by gaps in the sampling.
+        for shape in [Shape::Sphere(1), Shape::FigureEight, Shape::LinkedRings] {
+            let data = sample(shape, 200, 1, Some(0.01), 0);
+            let dm = to_dist_mat(&data.points, None);
+
+            let n = data.points.len();
+            let mut adj: SparseMatrix<f64> = SparseMatrix::new(n, n);
+            for a in 0..n {
+                for b in 0..n {
+                    match dm.get(a, b) {
+                        Some(len) if a != b && *len <= 0.6 => adj.set(a, b, *len),
+                        _ => (),
+                    }
+                }
+            }
+
+            let complex: SimplicialComplex = VietorisRips::compute_weighted(None, &adj, 3);
+            let diagram = persistence(&complex).unwrap();
+            let betti: Vec<usize> = (0..data.betti.len())
+                .map(|p| {
+                    diagram
+                        .iter()
+                        .filter(|pair| pair.dim == p && pair.death - pair.birth > 0.3)
+                        .count()
+                })
+                .collect();
+            assert_eq!(betti, data.betti, "{:?}", shape);
+        }
+    }
+}
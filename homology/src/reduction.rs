@@ -3,6 +3,28 @@
 //! Referenced "Computational Topology An Introduction" by Edelsbrunner and
 //! Harer. ISBN 978-0-8218-4925-5
 use common::{Matrix, MatrixOps};
+use std::ops::Range;
+
+/// Take each entry within the rows and columns given mod 2, so that
+/// repeated additions never overflow.
+fn normalize<M: Matrix<usize>>(mat: &mut M, (rows, cols): (Range<usize>, Range<usize>)) {
+    for r in rows {
+        for c in cols.clone() {
+            if let Some(v) = mat.get(r, c) {
+                let v = v % 2;
+                mat.set(r, c, v);
+            }
+        }
+    }
+}
+
+/// Return the first odd entry below and to the right of (x, x), by row.
+fn find_odd<M: Matrix<usize>>(mat: &M, x: usize) -> Option<(usize, usize)> {
+    let (rows, cols) = mat.dim();
+    (x..rows)
+        .flat_map(|k| (x..cols).map(move |l| (k, l)))
+        .find(|(k, l)| mat.get(*k, *l).is_some_and(|v| v % 2 == 1))
+}
 
 /// The reduction algorithm mod 2 presented on pg. 88.
 pub fn reduce_z2<M>(mat: &mut M, x: usize)
@@ -14,33 +36,33 @@ where
     // a:1,2;    a:2,2;    ...; a:np,2
     // a:1,np-1; a:2,np-1; ...; a:np,np-1
 
-    for k in x..rows {
-        for l in x..cols {
-            if let Some(v) = mat.get(k, l) {
+    // Each odd entry found is moved to (x, x) and its row and column
+    // cleared; the remainder is then reduced below and to the right, a
+    // pivot at a time rather than a call deeper per pivot.
+    let mut x = x;
+    while let Some((k, l)) = find_odd(mat, x) {
+        mat.row_swap(x, k);
+        mat.col_swap(x, l);
+
+        for i in x + 1..rows {
+            if let Some(v) = mat.get(i, x) {
                 if v % 2 == 1 {
-                    mat.row_swap(x, k);
-                    mat.col_swap(x, l);
-
-                    for i in x + 1..rows {
-                        if let Some(v) = mat.get(i, x) {
-                            if v % 2 == 1 {
-                                mat.row_add(x, i);
-                            }
-                        }
-                    }
-
-                    for j in x + 1..cols {
-                        if let Some(v) = mat.get(x, j) {
-                            if v % 2 == 1 {
-                                mat.col_add(x, j);
-                            }
-                        }
-                    }
-
-                    reduce_z2(mat, x + 1);
+                    mat.row_add(x, i);
+                    normalize(mat, (i..i + 1, 0..cols));
                 }
             }
         }
+
+        for j in x + 1..cols {
+            if let Some(v) = mat.get(x, j) {
+                if v % 2 == 1 {
+                    mat.col_add(x, j);
+                    normalize(mat, (0..rows, j..j + 1));
+                }
+            }
+        }
+
+        x += 1;
     }
 }
 
@@ -49,6 +71,46 @@ mod tests {
     use super::*;
     use common::dense::DenseMatrix;
 
+    /// A DenseMatrix counting the row swaps made on it.
+    struct Swaps(DenseMatrix<usize>, usize);
+
+    impl Matrix<usize> for Swaps {
+        fn new(r: usize, c: usize) -> Self {
+            Swaps(DenseMatrix::new(r, c), 0)
+        }
+
+        fn dim(&self) -> (usize, usize) {
+            self.0.dim()
+        }
+
+        fn set(&mut self, r: usize, c: usize, value: usize) {
+            self.0.set(r, c, value)
+        }
+
+        fn get(&self, r: usize, c: usize) -> Option<&usize> {
+            self.0.get(r, c)
+        }
+    }
+
+    impl MatrixOps<usize> for Swaps {
+        fn col_swap(&mut self, x: usize, y: usize) {
+            self.0.col_swap(x, y)
+        }
+
+        fn row_swap(&mut self, x: usize, y: usize) {
+            self.1 += 1;
+            self.0.row_swap(x, y)
+        }
+
+        fn col_add(&mut self, x: usize, y: usize) {
+            self.0.col_add(x, y)
+        }
+
+        fn row_add(&mut self, x: usize, y: usize) {
+            self.0.row_add(x, y)
+        }
+    }
+
     #[test]
     fn test_reduce_case_1() {
         let mut mat = DenseMatrix::<usize>::new(4, 6);
@@ -156,4 +218,69 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_reduce_no_overflow() {
+        // An upper triangular matrix of ones; each entry is added into
+        // those to its right at every step, doubling them, so unreduced
+        // entries overflow long before the end.
+        let n = 80;
+        let mut mat = DenseMatrix::<usize>::new(n, n);
+        for r in 0..n {
+            for c in r..n {
+                mat.set(r, c, 1);
+            }
+        }
+
+        reduce_z2(&mut mat, 0);
+
+        for r in 0..n {
+            for c in 0..n {
+                assert_eq!(*mat.get(r, c).unwrap() % 2, (r == c) as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduce_once_per_pivot() {
+        // Once the remainder below and to the right is reduced, nothing is
+        // left to do; each pivot is moved into place once.
+        let n = 8;
+        let mut mat = Swaps::new(n, n);
+        for r in 0..n {
+            for c in 0..n {
+                mat.set(r, c, (r == c) as usize);
+            }
+        }
+
+        reduce_z2(&mut mat, 0);
+        assert_eq!(mat.1, n);
+        for r in 0..n {
+            for c in 0..n {
+                assert_eq!(*mat.get(r, c).unwrap() % 2, (r == c) as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduce_stack() {
+        // One pivot after another, within a stack far smaller than the
+        // recursion of one call per pivot would need.
+        let n = 2000;
+        let reduced = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(move || {
+                let mut mat = DenseMatrix::<usize>::new(n, n);
+                for i in 0..n {
+                    mat.set(i, i, 1);
+                }
+
+                reduce_z2(&mut mat, 0);
+                (0..n).all(|i| mat.get(i, i) == Some(&1))
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(reduced);
+    }
 }
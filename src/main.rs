@@ -5,7 +5,7 @@ use std::io;
 use std::str::FromStr;
 
 use complex::vietoris_rips;
-use distance::{neighborhood, point_cloud};
+use distance::neighborhood;
 use viz::graphviz;
 
 fn read() -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
@@ -29,8 +29,8 @@ fn read() -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
 fn main() {
     match read() {
         Ok(r) => {
-            let adj = neighborhood::to_adjacency(&point_cloud::to_dist_mat(&r, None), 0.5);
-            let sc = vietoris_rips::VietorisRips::compute(None, &adj, 3, 0);
+            let adj = neighborhood::cloud_to_weighted_adjacency(&r, 0.5, None);
+            let sc = vietoris_rips::VietorisRips::compute_weighted(None, &adj, 3);
             if let Ok(mut v) = File::create("example.dot") {
                 graphviz::render_to(&sc, &mut v);
             }
@@ -1,6 +1,7 @@
 // viz/src/graphviz.rs
 /// Transform a SimplicialComplex into a format suitable for representation
 /// via GraphViz.
+use common::{float::Float, sparse::SparseMatrix};
 use complex::SimplicialComplex;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
@@ -13,7 +14,7 @@ type Ed = (usize, usize);
 struct Graph {
     nodes: Vec<Nd>,
     edges: Vec<Ed>,
-    weights: HashMap<Ed, usize>,
+    weights: HashMap<Ed, f64>,
 }
 
 /// Transform a SimplicialComplex into the GraphViz format, writing it to
@@ -23,6 +24,13 @@ pub fn render_to<W: Write>(complex: &SimplicialComplex, output: &mut W) {
     dot::render(&tmp, output).unwrap()
 }
 
+/// Transform a weighted neighborhood graph into the GraphViz format, writing
+/// it to disk. Each edge is labelled with its length.
+pub fn render_graph_to<F: Float, W: Write>(adj: &SparseMatrix<F>, output: &mut W) {
+    let tmp: Graph = adj.into();
+    dot::render(&tmp, output).unwrap()
+}
+
 impl<'a> dot::Labeller<'a, Nd, Ed> for Graph {
     fn graph_id(&'a self) -> dot::Id<'a> {
         dot::Id::new("example").unwrap()
@@ -63,7 +71,7 @@ impl From<&SimplicialComplex> for Graph {
     fn from(complex: &SimplicialComplex) -> Graph {
         let mut nodes: HashSet<Nd> = HashSet::new();
         let mut edges: HashSet<Ed> = HashSet::new();
-        let mut weights: HashMap<Ed, usize> = HashMap::new();
+        let mut weights: HashMap<Ed, f64> = HashMap::new();
 
         // To produce the entirety of the graph, we just need to produce
         // the 1-skeleton of the SimplicialComplex.
@@ -76,9 +84,9 @@ impl From<&SimplicialComplex> for Graph {
                 }
 
                 1 => {
-                    let mut iter = simplex.into_iter();
-                    let mut a = iter.next().unwrap();
-                    let mut b = iter.next().unwrap();
+                    let mut iter = (&simplex).into_iter();
+                    let mut a = iter.next().unwrap().id();
+                    let mut b = iter.next().unwrap().id();
 
                     // Order the vertices.
                     match a < b {
@@ -86,8 +94,9 @@ impl From<&SimplicialComplex> for Graph {
                         false => std::mem::swap(&mut a, &mut b),
                     };
 
-                    edges.insert((a.id(), b.id()));
-                    weights.insert((a.id(), b.id()), a.weight());
+                    // The weight of an edge is its length.
+                    edges.insert((a, b));
+                    weights.insert((a, b), simplex.weight());
                 }
                 _ => continue,
             }
@@ -101,11 +110,109 @@ impl From<&SimplicialComplex> for Graph {
     }
 }
 
+impl<F: Float> From<&SparseMatrix<F>> for Graph {
+    fn from(adj: &SparseMatrix<F>) -> Graph {
+        let mut nodes: HashSet<Nd> = HashSet::new();
+        let mut edges: HashSet<Ed> = HashSet::new();
+        let mut weights: HashMap<Ed, f64> = HashMap::new();
+
+        // Each point is a node, regardless of whether it has neighbors.
+        let (rows, _) = common::Matrix::dim(adj);
+        nodes.extend(0..rows);
+
+        // The matrix is symmetric; keep only the upper triangle, without
+        // the diagonal.
+        for ((r, c), len) in adj.iter() {
+            if r < c {
+                edges.insert((r, c));
+                weights.insert((r, c), (*len).into());
+            }
+        }
+
+        Graph {
+            nodes: Vec::from_iter(nodes),
+            edges: Vec::from_iter(edges),
+            weights,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use common::Matrix;
+    use complex::vietoris_rips::VietorisRips;
+    use complex::ComplexBuilder;
 
     #[test]
     fn test_simplicial_complex_to_graph() {
-        panic!("Write this test!")
+        // A filled triangle on the vertices 0, 1, 2.
+        let complex: SimplicialComplex =
+            ComplexBuilder::new().maximal([[0, 1, 2]]).unwrap().build();
+        let graph: Graph = (&complex).into();
+
+        let nodes: HashSet<Nd> = graph.nodes.iter().copied().collect();
+        assert_eq!(nodes, HashSet::from([0, 1, 2]));
+
+        // Only the 1-skeleton is rendered, each edge ordered by vertex.
+        let edges: HashSet<Ed> = graph.edges.iter().copied().collect();
+        assert_eq!(edges, HashSet::from([(0, 1), (0, 2), (1, 2)]));
+        for edge in &edges {
+            assert_eq!(graph.weights.get(edge), Some(&0.0));
+        }
+
+        let mut output = Vec::new();
+        render_to(&complex, &mut output);
+        assert!(String::from_utf8(output).unwrap().contains("N0 -> N1"));
+    }
+
+    #[test]
+    fn test_weighted_graph_to_graph() {
+        // A triangle with sides of length 1, 2 and 3.
+        let mut adj: SparseMatrix<f64> = SparseMatrix::new(3, 3);
+        for (a, b, len) in [(0, 1, 1.0), (0, 2, 2.0), (1, 2, 3.0)] {
+            adj.set(a, b, len);
+            adj.set(b, a, len);
+        }
+
+        // Both paths should label each edge with its length.
+        let from_adj: Graph = (&adj).into();
+        let complex = VietorisRips::compute_weighted(None, &adj, 2);
+        let from_complex: Graph = (&complex).into();
+
+        for graph in [from_adj, from_complex] {
+            assert_eq!(graph.nodes.len(), 3);
+            assert_eq!(graph.edges.len(), 3);
+            assert_eq!(graph.weights.get(&(0, 1)), Some(&1.0));
+            assert_eq!(graph.weights.get(&(0, 2)), Some(&2.0));
+            assert_eq!(graph.weights.get(&(1, 2)), Some(&3.0));
+        }
+
+        let mut output = Vec::new();
+        render_graph_to(&adj, &mut output);
+        assert!(String::from_utf8(output).unwrap().contains("label=\"3\""));
+    }
+
+    #[test]
+    fn test_built_complex_to_graph() {
+        // A filled square, split along a diagonal, and a lone vertex.
+        let complex: SimplicialComplex = ComplexBuilder::new()
+            .simplex(&[0, 1, 2], Some(2.0))
+            .and_then(|b| b.simplex(&[0, 2, 3], Some(2.0)))
+            .and_then(|b| b.simplex(&[0, 1], Some(1.0)))
+            .and_then(|b| b.simplex(&[4], None))
+            .unwrap()
+            .build();
+        let graph: Graph = (&complex).into();
+
+        let nodes: HashSet<Nd> = graph.nodes.iter().copied().collect();
+        assert_eq!(nodes, HashSet::from([0, 1, 2, 3, 4]));
+        let edges: HashSet<Ed> = graph.edges.iter().copied().collect();
+        assert_eq!(
+            edges,
+            HashSet::from([(0, 1), (0, 2), (0, 3), (1, 2), (2, 3)])
+        );
+        assert_eq!(graph.weights.get(&(0, 1)), Some(&1.0));
+        assert_eq!(graph.weights.get(&(0, 2)), Some(&2.0));
     }
 }